@@ -0,0 +1,584 @@
+use std::{
+    env,
+    fmt::Write as _,
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+/// Structs whose layout is checked, with the C names of their fields.
+const STRUCTS: &[(&str, &[&str])] = &[
+    (
+        "CameraAbilities",
+        &[
+            "model",
+            "status",
+            "port",
+            "speed",
+            "operations",
+            "file_operations",
+            "folder_operations",
+            "usb_vendor",
+            "usb_product",
+            "usb_class",
+            "usb_subclass",
+            "usb_protocol",
+            "library",
+            "id",
+            "device_type",
+            "reserved2",
+            "reserved3",
+            "reserved4",
+            "reserved5",
+            "reserved6",
+            "reserved7",
+            "reserved8",
+        ],
+    ),
+    (
+        "CameraFileInfoFile",
+        &[
+            "fields",
+            "status",
+            "size",
+            "type",
+            "width",
+            "height",
+            "permissions",
+            "mtime",
+        ],
+    ),
+    (
+        "CameraFileInfoPreview",
+        &["fields", "status", "size", "type", "width", "height"],
+    ),
+    ("CameraFileInfoAudio", &["fields", "status", "size", "type"]),
+    ("CameraFileInfo", &["preview", "file", "audio"]),
+    (
+        "CameraStorageInformation",
+        &[
+            "fields",
+            "basedir",
+            "label",
+            "description",
+            "type",
+            "fstype",
+            "access",
+            "capacitykbytes",
+            "freekbytes",
+            "freeimages",
+        ],
+    ),
+    (
+        "CameraFilesystemFuncs",
+        &[
+            "file_list_func",
+            "folder_list_func",
+            "put_file_func",
+            "delete_all_func",
+            "get_info_func",
+            "set_info_func",
+            "make_dir_func",
+            "remove_dir_func",
+            "get_file_func",
+            "read_file_func",
+            "del_file_func",
+            "storage_info_func",
+            "unused",
+        ],
+    ),
+    ("CameraFilePath", &["name", "folder"]),
+    ("CameraText", &["text"]),
+    ("Camera", &["port", "fs", "functions", "pl", "pc"]),
+    (
+        "CameraFunctions",
+        &[
+            "pre_func",
+            "post_func",
+            "exit",
+            "get_config",
+            "set_config",
+            "list_config",
+            "get_single_config",
+            "set_single_config",
+            "capture",
+            "trigger_capture",
+            "capture_preview",
+            "summary",
+            "manual",
+            "about",
+            "wait_for_event",
+            "reserved1",
+        ],
+    ),
+    ("CameraFileHandler", &["size", "read", "write"]),
+    (
+        "GPPortSettingsSerial",
+        &["port", "speed", "bits", "parity", "stopbits"],
+    ),
+    (
+        "GPPortSettingsUSB",
+        &[
+            "inep",
+            "outep",
+            "intep",
+            "config",
+            "interface",
+            "altsetting",
+            "maxpacketsize",
+            "port",
+        ],
+    ),
+    ("GPPortSettingsUsbDiskDirect", &["path"]),
+    ("GPPortSettingsUsbScsi", &["path"]),
+    (
+        "GPPortSettings",
+        &["serial", "usb", "usbdiskdirect", "usbscsi"],
+    ),
+    (
+        "GPPortOperations",
+        &[
+            "init",
+            "exit",
+            "open",
+            "close",
+            "read",
+            "check_int",
+            "write",
+            "update",
+            "get_pin",
+            "set_pin",
+            "send_break",
+            "flush",
+            "find_device",
+            "find_device_by_class",
+            "clear_halt",
+            "msg_write",
+            "msg_read",
+            "msg_interface_write",
+            "msg_interface_read",
+            "msg_class_write",
+            "msg_class_read",
+            "seek",
+            "send_scsi_cmd",
+            "reset",
+        ],
+    ),
+    (
+        "GPPort",
+        &[
+            "type",
+            "settings",
+            "settings_pending",
+            "timeout",
+            "pl",
+            "pc",
+        ],
+    ),
+];
+
+/// Enums whose size and discriminants are checked.
+const ENUMS: &[(&str, &[&str])] = &[
+    (
+        "GPContextFeedback",
+        &["GP_CONTEXT_FEEDBACK_OK", "GP_CONTEXT_FEEDBACK_CANCEL"],
+    ),
+    (
+        "CameraDriverStatus",
+        &[
+            "GP_DRIVER_STATUS_PRODUCTION",
+            "GP_DRIVER_STATUS_TESTING",
+            "GP_DRIVER_STATUS_EXPERIMENTAL",
+            "GP_DRIVER_STATUS_DEPRECATED",
+        ],
+    ),
+    (
+        "GphotoDeviceType",
+        &["GP_DEVICE_STILL_CAMERA", "GP_DEVICE_AUDIO_PLAYER"],
+    ),
+    (
+        "CameraOperation",
+        &[
+            "GP_OPERATION_NONE",
+            "GP_OPERATION_CAPTURE_IMAGE",
+            "GP_OPERATION_CAPTURE_VIDEO",
+            "GP_OPERATION_CAPTURE_AUDIO",
+            "GP_OPERATION_CAPTURE_PREVIEW",
+            "GP_OPERATION_CONFIG",
+            "GP_OPERATION_TRIGGER_CAPTURE",
+        ],
+    ),
+    (
+        "CameraFileOperation",
+        &[
+            "GP_FILE_OPERATION_NONE",
+            "GP_FILE_OPERATION_DELETE",
+            "GP_FILE_OPERATION_PREVIEW",
+            "GP_FILE_OPERATION_RAW",
+            "GP_FILE_OPERATION_AUDIO",
+            "GP_FILE_OPERATION_EXIF",
+        ],
+    ),
+    (
+        "CameraFolderOperation",
+        &[
+            "GP_FOLDER_OPERATION_NONE",
+            "GP_FOLDER_OPERATION_DELETE_ALL",
+            "GP_FOLDER_OPERATION_PUT_FILE",
+            "GP_FOLDER_OPERATION_MAKE_DIR",
+            "GP_FOLDER_OPERATION_REMOVE_DIR",
+        ],
+    ),
+    (
+        "CameraCaptureType",
+        &["GP_CAPTURE_IMAGE", "GP_CAPTURE_MOVIE", "GP_CAPTURE_SOUND"],
+    ),
+    (
+        "CameraEventType",
+        &[
+            "GP_EVENT_UNKNOWN",
+            "GP_EVENT_TIMEOUT",
+            "GP_EVENT_FILE_ADDED",
+            "GP_EVENT_FOLDER_ADDED",
+            "GP_EVENT_CAPTURE_COMPLETE",
+            "GP_EVENT_FILE_CHANGED",
+        ],
+    ),
+    (
+        "CameraFileType",
+        &[
+            "GP_FILE_TYPE_PREVIEW",
+            "GP_FILE_TYPE_NORMAL",
+            "GP_FILE_TYPE_RAW",
+            "GP_FILE_TYPE_AUDIO",
+            "GP_FILE_TYPE_EXIF",
+            "GP_FILE_TYPE_METADATA",
+        ],
+    ),
+    (
+        "CameraFileAccessType",
+        &[
+            "GP_FILE_ACCESSTYPE_MEMORY",
+            "GP_FILE_ACCESSTYPE_FD",
+            "GP_FILE_ACCESSTYPE_HANDLER",
+        ],
+    ),
+    (
+        "CameraFileInfoFields",
+        &[
+            "GP_FILE_INFO_NONE",
+            "GP_FILE_INFO_TYPE",
+            "GP_FILE_INFO_SIZE",
+            "GP_FILE_INFO_WIDTH",
+            "GP_FILE_INFO_HEIGHT",
+            "GP_FILE_INFO_PERMISSIONS",
+            "GP_FILE_INFO_STATUS",
+            "GP_FILE_INFO_MTIME",
+            "GP_FILE_INFO_ALL",
+        ],
+    ),
+    (
+        "CameraFilePermissions",
+        &[
+            "GP_FILE_PERM_NONE",
+            "GP_FILE_PERM_READ",
+            "GP_FILE_PERM_DELETE",
+            "GP_FILE_PERM_ALL",
+        ],
+    ),
+    (
+        "CameraFileStatus",
+        &["GP_FILE_STATUS_NOT_DOWNLOADED", "GP_FILE_STATUS_DOWNLOADED"],
+    ),
+    (
+        "CameraStorageInfoFields",
+        &[
+            "GP_STORAGEINFO_BASE",
+            "GP_STORAGEINFO_LABEL",
+            "GP_STORAGEINFO_DESCRIPTION",
+            "GP_STORAGEINFO_ACCESS",
+            "GP_STORAGEINFO_STORAGETYPE",
+            "GP_STORAGEINFO_FILESYSTEMTYPE",
+            "GP_STORAGEINFO_MAXCAPACITY",
+            "GP_STORAGEINFO_FREESPACEKBYTES",
+            "GP_STORAGEINFO_FREESPACEIMAGES",
+        ],
+    ),
+    (
+        "CameraStorageType",
+        &[
+            "GP_STORAGEINFO_ST_UNKNOWN",
+            "GP_STORAGEINFO_ST_FIXED_ROM",
+            "GP_STORAGEINFO_ST_REMOVABLE_ROM",
+            "GP_STORAGEINFO_ST_FIXED_RAM",
+            "GP_STORAGEINFO_ST_REMOVABLE_RAM",
+        ],
+    ),
+    (
+        "CameraStorageAccessType",
+        &[
+            "GP_STORAGEINFO_AC_READWRITE",
+            "GP_STORAGEINFO_AC_READONLY",
+            "GP_STORAGEINFO_AC_READONLY_WITH_DELETE",
+        ],
+    ),
+    (
+        "CameraStorageFilesystemType",
+        &[
+            "GP_STORAGEINFO_FST_UNDEFINED",
+            "GP_STORAGEINFO_FST_GENERICFLAT",
+            "GP_STORAGEINFO_FST_GENERICHIERARCHICAL",
+            "GP_STORAGEINFO_FST_DCF",
+        ],
+    ),
+    (
+        "CameraWidgetType",
+        &[
+            "GP_WIDGET_WINDOW",
+            "GP_WIDGET_SECTION",
+            "GP_WIDGET_TEXT",
+            "GP_WIDGET_RANGE",
+            "GP_WIDGET_TOGGLE",
+            "GP_WIDGET_RADIO",
+            "GP_WIDGET_MENU",
+            "GP_WIDGET_BUTTON",
+            "GP_WIDGET_DATE",
+        ],
+    ),
+    (
+        "GPPortType",
+        &[
+            "GP_PORT_NONE",
+            "GP_PORT_SERIAL",
+            "GP_PORT_USB",
+            "GP_PORT_DISK",
+            "GP_PORT_PTPIP",
+            "GP_PORT_USB_DISK_DIRECT",
+            "GP_PORT_USB_SCSI",
+            "GP_PORT_IP",
+        ],
+    ),
+    (
+        "GPPortSerialParity",
+        &[
+            "GP_PORT_SERIAL_PARITY_OFF",
+            "GP_PORT_SERIAL_PARITY_EVEN",
+            "GP_PORT_SERIAL_PARITY_ODD",
+        ],
+    ),
+    (
+        "GPPin",
+        &[
+            "GP_PIN_RTS",
+            "GP_PIN_DTR",
+            "GP_PIN_CTS",
+            "GP_PIN_DSR",
+            "GP_PIN_CD",
+            "GP_PIN_RING",
+        ],
+    ),
+    ("GPLevel", &["GP_LEVEL_LOW", "GP_LEVEL_HIGH"]),
+    (
+        "GPLogLevel",
+        &[
+            "GP_LOG_ERROR",
+            "GP_LOG_VERBOSE",
+            "GP_LOG_DEBUG",
+            "GP_LOG_DATA",
+        ],
+    ),
+    (
+        "GPVersionVerbosity",
+        &["GP_VERSION_SHORT", "GP_VERSION_VERBOSE"],
+    ),
+];
+
+/// Preprocessor constants whose values are checked.
+const CONSTANTS: &[&str] = &[
+    "GP_PORT_MAX_BUF_LEN",
+    "GP_PORT_USB_ENDPOINT_IN",
+    "GP_PORT_USB_ENDPOINT_OUT",
+    "GP_PORT_USB_ENDPOINT_INT",
+    "GP_OK",
+    "GP_ERROR",
+    "GP_ERROR_BAD_PARAMETERS",
+    "GP_ERROR_NO_MEMORY",
+    "GP_ERROR_LIBRARY",
+    "GP_ERROR_UNKNOWN_PORT",
+    "GP_ERROR_NOT_SUPPORTED",
+    "GP_ERROR_IO",
+    "GP_ERROR_FIXED_LIMIT_EXCEEDED",
+    "GP_ERROR_TIMEOUT",
+    "GP_ERROR_IO_SUPPORTED_SERIAL",
+    "GP_ERROR_IO_SUPPORTED_USB",
+    "GP_ERROR_IO_INIT",
+    "GP_ERROR_IO_READ",
+    "GP_ERROR_IO_WRITE",
+    "GP_ERROR_IO_UPDATE",
+    "GP_ERROR_IO_SERIAL_SPEED",
+    "GP_ERROR_IO_USB_CLEAR_HALT",
+    "GP_ERROR_IO_USB_FIND",
+    "GP_ERROR_IO_USB_CLAIM",
+    "GP_ERROR_IO_LOCK",
+    "GP_ERROR_HAL",
+    "GP_ERROR_CORRUPTED_DATA",
+    "GP_ERROR_FILE_EXISTS",
+    "GP_ERROR_MODEL_NOT_FOUND",
+    "GP_ERROR_DIRECTORY_NOT_FOUND",
+    "GP_ERROR_FILE_NOT_FOUND",
+    "GP_ERROR_DIRECTORY_EXISTS",
+    "GP_ERROR_CAMERA_BUSY",
+    "GP_ERROR_PATH_NOT_ABSOLUTE",
+    "GP_ERROR_CANCEL",
+    "GP_ERROR_CAMERA_ERROR",
+    "GP_ERROR_OS_FAILURE",
+    "GP_ERROR_NO_SPACE",
+];
+
+/// Items that only exist since some release, with the `cfg` of that release
+/// (see `build/version.rs`). Fields are given as `Struct.field`.
+const VERSIONED: &[(&str, &str)] = &[
+    ("v2_5_10", "CameraFilesystemFuncs.read_file_func"),
+    ("v2_5_27", "GP_EVENT_FILE_CHANGED"),
+    ("v2_5_27", "GP_ERROR_NO_SPACE"),
+];
+
+/// Writes `OUT_DIR/layout.rs` with the sizes, alignments, field offsets and
+/// constant values the installed headers define, for `tests/layout.rs`.
+///
+/// This compiles and runs a small C program against the headers. If that is
+/// not possible the file records why instead, so the tests can report it
+/// without failing the build of the library itself.
+/// Items of releases newer than the enabled `cfgs` are left out.
+pub fn generate(include_paths: &[PathBuf], cfgs: &[&str]) {
+    let out_dir = PathBuf::from(env::var_os("OUT_DIR").unwrap());
+    let output = out_dir.join("layout.rs");
+
+    let layout = if env::var("TARGET").ok() != env::var("HOST").ok() {
+        Err("cannot run the layout generator when cross compiling".to_string())
+    } else {
+        run_generator(&out_dir, include_paths, cfgs)
+    };
+
+    let contents = match layout {
+        Ok(layout) => format!("pub const ERROR: Option<&str> = None;\n{}", layout),
+        Err(err) => {
+            println!(
+                "cargo:warning=ABI layout of the libgphoto2 headers unavailable: {}",
+                err.lines().next().unwrap_or_default()
+            );
+            format!(
+                "pub const ERROR: Option<&str> = Some({:?});\n\
+                 pub const TYPES: &[(&str, usize, usize)] = &[];\n\
+                 pub const FIELDS: &[(&str, &str, usize, usize)] = &[];\n\
+                 pub const CONSTANTS: &[(&str, i64)] = &[];\n",
+                err
+            )
+        }
+    };
+
+    fs::write(output, contents).expect("failed to write layout.rs");
+}
+
+fn generator_source(cfgs: &[&str]) -> String {
+    let available = |item: &str| {
+        VERSIONED
+            .iter()
+            .all(|(cfg, versioned)| *versioned != item || cfgs.contains(cfg))
+    };
+
+    let mut source = String::from(
+        "#include <stdio.h>\n\
+         #include <stddef.h>\n\
+         #include <gphoto2/gphoto2.h>\n\
+         #include <gphoto2/gphoto2-port.h>\n\
+         #include <gphoto2/gphoto2-port-log.h>\n\
+         #include <gphoto2/gphoto2-port-library.h>\n\n\
+         #define TYPE(t) printf(\"    (\\\"%s\\\", %zu, %zu),\\n\", #t, sizeof(t), _Alignof(t));\n\
+         #define FIELD(t, f) printf(\"    (\\\"%s\\\", \\\"%s\\\", %zu, %zu),\\n\", \
+         #t, #f, offsetof(t, f), sizeof(((t *)0)->f));\n\
+         #define CONSTANT(c) printf(\"    (\\\"%s\\\", %lld),\\n\", #c, (long long)(c));\n\n\
+         int main(void) {\n",
+    );
+
+    source.push_str("    printf(\"pub const TYPES: &[(&str, usize, usize)] = &[\\n\");\n");
+    for (name, _) in STRUCTS.iter().chain(ENUMS) {
+        writeln!(source, "    TYPE({})", name).unwrap();
+    }
+    source.push_str("    printf(\"];\\n\");\n");
+
+    source.push_str("    printf(\"pub const FIELDS: &[(&str, &str, usize, usize)] = &[\\n\");\n");
+    for (name, fields) in STRUCTS {
+        for field in fields
+            .iter()
+            .filter(|field| available(&format!("{}.{}", name, field)))
+        {
+            writeln!(source, "    FIELD({}, {})", name, field).unwrap();
+        }
+    }
+    source.push_str("    printf(\"];\\n\");\n");
+
+    source.push_str("    printf(\"pub const CONSTANTS: &[(&str, i64)] = &[\\n\");\n");
+    for constant in ENUMS
+        .iter()
+        .flat_map(|(_, variants)| variants.iter())
+        .chain(CONSTANTS)
+        .filter(|constant| available(constant))
+    {
+        writeln!(source, "    CONSTANT({})", constant).unwrap();
+    }
+    source.push_str("    printf(\"];\\n\");\n");
+
+    source.push_str("    return 0;\n}\n");
+    source
+}
+
+fn run_generator(
+    out_dir: &Path,
+    include_paths: &[PathBuf],
+    cfgs: &[&str],
+) -> Result<String, String> {
+    let source = out_dir.join("layout.c");
+    let exe = out_dir.join(if cfg!(windows) {
+        "layout.exe"
+    } else {
+        "layout"
+    });
+
+    fs::write(&source, generator_source(cfgs)).map_err(|err| err.to_string())?;
+
+    let compiler = cc::Build::new()
+        .includes(include_paths)
+        .cargo_metadata(false)
+        .try_get_compiler()
+        .map_err(|err| err.to_string())?;
+
+    let mut command = compiler.to_command();
+    for path in include_paths {
+        command.arg(format!("-I{}", path.display()));
+    }
+    if compiler.is_like_msvc() {
+        command.arg(&source).arg(format!("/Fe{}", exe.display()));
+    } else {
+        command.arg(&source).arg("-o").arg(&exe);
+    }
+
+    let compiled = command
+        .output()
+        .map_err(|err| format!("failed to run the C compiler: {}", err))?;
+    if !compiled.status.success() {
+        return Err(format!(
+            "failed to compile the layout generator against the libgphoto2 headers:\n{}",
+            String::from_utf8_lossy(&compiled.stderr)
+        ));
+    }
+
+    let generated = Command::new(&exe)
+        .output()
+        .map_err(|err| format!("failed to run the layout generator: {}", err))?;
+    if !generated.status.success() {
+        return Err("the layout generator failed".to_string());
+    }
+
+    String::from_utf8(generated.stdout).map_err(|err| err.to_string())
+}
@@ -0,0 +1,56 @@
+use std::{env, path::PathBuf};
+
+mod layout;
+#[cfg(not(feature = "vendored"))]
+mod system;
+#[cfg(feature = "vendored")]
+mod vendored;
+mod version;
+
+/// The libgphoto2 the bindings are built against.
+struct Library {
+    include_paths: Vec<PathBuf>,
+    /// The libgphoto2 version, if it is known.
+    version: Option<String>,
+}
+
+fn main() {
+    for var in [
+        "LIBGPHOTO2_DIR",
+        "LIBGPHOTO2_LIB_DIR",
+        "LIBGPHOTO2_INCLUDE_DIR",
+        "LIBGPHOTO2_STATIC",
+        "LIBGPHOTO2_NO_PKG_CONFIG",
+        "LIBGPHOTO2_SRC_DIR",
+        "LIBGPHOTO2_VERSION",
+    ] {
+        println!("cargo:rerun-if-env-changed={}", var);
+    }
+
+    #[cfg(feature = "vendored")]
+    let library = vendored::build();
+
+    #[cfg(all(not(feature = "vendored"), not(feature = "runtime-load")))]
+    let library = system::link();
+
+    // The library is opened by `load()`, only look for the headers
+    #[cfg(feature = "runtime-load")]
+    let library = system::probe();
+
+    let cfgs = version::emit_cfgs(library.version.as_deref());
+    // Exposed to dependents as DEP_GPHOTO2_CFGS
+    println!("cargo:cfgs={}", cfgs.join(","));
+
+    layout::generate(&library.include_paths, &cfgs);
+
+    // Exposed to dependents as DEP_GPHOTO2_INCLUDE
+    if let Ok(include) = env::join_paths(&library.include_paths) {
+        println!("cargo:include={}", include.to_string_lossy());
+    }
+}
+
+fn env_path(var: &str) -> Option<PathBuf> {
+    env::var_os(var)
+        .filter(|v| !v.is_empty())
+        .map(PathBuf::from)
+}
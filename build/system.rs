@@ -0,0 +1,127 @@
+// `link` and `probe` are used depending on the enabled features
+#![allow(dead_code)]
+
+use std::{env, path::PathBuf};
+
+use crate::{env_path, Library};
+
+/// The pkg-config package, library name and minimum version we need, in link order.
+///
+/// libgphoto2_port is versioned separately from libgphoto2.
+const PACKAGES: [(&str, &str, &str); 2] = [
+    ("libgphoto2", "gphoto2", "2.5.0"),
+    ("libgphoto2_port", "gphoto2_port", "0.10.0"),
+];
+
+/// Links against an installed libgphoto2.
+pub fn link() -> Library {
+    match manual_paths() {
+        Some(paths) => link_manual(paths),
+        None => link_pkg_config(),
+    }
+}
+
+/// Include directories and version of an installed libgphoto2, if any,
+/// without linking against it.
+pub fn probe() -> Library {
+    if let Some((_, include_dir)) = manual_paths() {
+        return Library {
+            include_paths: include_dir.into_iter().collect(),
+            version: None,
+        };
+    }
+
+    match pkg_config::Config::new()
+        .cargo_metadata(false)
+        .probe("libgphoto2")
+    {
+        Ok(library) => Library {
+            include_paths: library.include_paths,
+            version: Some(library.version),
+        },
+        Err(_) => Library {
+            include_paths: Vec::new(),
+            version: None,
+        },
+    }
+}
+
+fn is_static() -> bool {
+    env::var("LIBGPHOTO2_STATIC").is_ok_and(|v| v != "0")
+}
+
+/// Library and include directories set through the `LIBGPHOTO2_*` environment
+/// variables, if pkg-config should be bypassed.
+///
+/// `LIBGPHOTO2_DIR` is an install prefix (containing `lib/` and `include/`),
+/// `LIBGPHOTO2_LIB_DIR` and `LIBGPHOTO2_INCLUDE_DIR` override either half.
+fn manual_paths() -> Option<(PathBuf, Option<PathBuf>)> {
+    let prefix = env_path("LIBGPHOTO2_DIR");
+    let lib_dir =
+        env_path("LIBGPHOTO2_LIB_DIR").or_else(|| prefix.as_ref().map(|p| p.join("lib")))?;
+    let include_dir =
+        env_path("LIBGPHOTO2_INCLUDE_DIR").or_else(|| prefix.as_ref().map(|p| p.join("include")));
+
+    Some((lib_dir, include_dir))
+}
+
+fn link_manual((lib_dir, include_dir): (PathBuf, Option<PathBuf>)) -> Library {
+    if !lib_dir.is_dir() {
+        panic!(
+            "LIBGPHOTO2_LIB_DIR (or LIBGPHOTO2_DIR/lib) points to `{}`, which is not a directory",
+            lib_dir.display()
+        );
+    }
+
+    let kind = if is_static() { "static" } else { "dylib" };
+
+    println!("cargo:rustc-link-search=native={}", lib_dir.display());
+    for (_, lib, _) in PACKAGES {
+        println!("cargo:rustc-link-lib={}={}", kind, lib);
+    }
+
+    Library {
+        include_paths: include_dir.into_iter().collect(),
+        version: None,
+    }
+}
+
+fn link_pkg_config() -> Library {
+    if env::var_os("LIBGPHOTO2_NO_PKG_CONFIG").is_some() {
+        panic!(
+            "LIBGPHOTO2_NO_PKG_CONFIG is set but neither LIBGPHOTO2_DIR nor LIBGPHOTO2_LIB_DIR \
+             were given, so libgphoto2 cannot be located"
+        );
+    }
+
+    let mut include_paths = Vec::new();
+    let mut libgphoto2_version = None;
+
+    for (package, _, version) in PACKAGES {
+        let library = pkg_config::Config::new()
+            .atleast_version(version)
+            .statik(is_static())
+            .probe(package)
+            .unwrap_or_else(|err| {
+                panic!(
+                    "could not find the `{}` package via pkg-config.\n\
+                     Install the libgphoto2 development files (e.g. `libgphoto2-dev`), \
+                     add its `.pc` directory to PKG_CONFIG_PATH, or point LIBGPHOTO2_DIR / \
+                     LIBGPHOTO2_LIB_DIR at a custom install.\n\n{}",
+                    package, err
+                )
+            });
+
+        include_paths.extend(library.include_paths);
+        // libgphoto2_port has its own version numbers
+        libgphoto2_version.get_or_insert(library.version);
+    }
+
+    include_paths.sort();
+    include_paths.dedup();
+
+    Library {
+        include_paths,
+        version: libgphoto2_version,
+    }
+}
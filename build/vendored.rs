@@ -0,0 +1,121 @@
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{env_path, Library};
+
+/// Builds the bundled libgphoto2 sources and links them dynamically.
+///
+/// Both libgphoto2 and libgphoto2_port (which the top level configure script
+/// builds as a subproject) are installed into `OUT_DIR`, so the camlib and
+/// iolib directories baked into the libraries point inside the build output
+/// and `gp_abilities_list_load` finds the drivers without a system install.
+///
+/// The drivers link to the installed shared libraries, so the crate must use
+/// them too: linking a static copy would load a second libgphoto2_port with
+/// its own state alongside the one the drivers use.
+pub fn build() -> Library {
+    let source = source_dir();
+    println!("cargo:rerun-if-changed={}", source.display());
+
+    let mut config = autotools::Config::new(&source);
+
+    // A git checkout has no configure script until it is bootstrapped
+    if !source.join("configure").exists() {
+        config.reconf("-ivf");
+    }
+
+    let prefix = config
+        .out_dir(PathBuf::from(env::var_os("OUT_DIR").unwrap()).join("vendor"))
+        // Camlibs and iolibs are libtool modules and can only be loaded when
+        // built shared
+        .disable_static()
+        .enable_shared()
+        .disable("nls", None)
+        .with("libexif", Some("no"))
+        .without("libxml-2.0", None)
+        .without("libcurl", None)
+        .without("gdlib", None)
+        .without("jpeg", None)
+        .without("doxygen", None)
+        .build();
+
+    let lib_dir = prefix.join("lib");
+
+    println!("cargo:rustc-link-search=native={}", lib_dir.display());
+    println!("cargo:rustc-link-lib=dylib=gphoto2");
+    println!("cargo:rustc-link-lib=dylib=gphoto2_port");
+    // Only applies to the targets of this crate, dependents get the directory
+    // as DEP_GPHOTO2_LIB_DIR to set the rpath of theirs
+    println!("cargo:rustc-link-arg=-Wl,-rpath,{}", lib_dir.display());
+    println!("cargo:lib_dir={}", lib_dir.display());
+
+    let camlibs = driver_dir(&lib_dir.join("libgphoto2"));
+    let iolibs = driver_dir(&lib_dir.join("libgphoto2_port"));
+
+    // Exposed to this crate as `vendored::CAMLIBS` / `vendored::IOLIBS` and to
+    // dependents as DEP_GPHOTO2_CAMLIBS / DEP_GPHOTO2_IOLIBS
+    println!(
+        "cargo:rustc-env=LIBGPHOTO2_VENDORED_CAMLIBS={}",
+        camlibs.display()
+    );
+    println!(
+        "cargo:rustc-env=LIBGPHOTO2_VENDORED_IOLIBS={}",
+        iolibs.display()
+    );
+    println!("cargo:camlibs={}", camlibs.display());
+    println!("cargo:iolibs={}", iolibs.display());
+
+    Library {
+        include_paths: vec![prefix.join("include")],
+        version: source_version(&source),
+    }
+}
+
+/// The libgphoto2 source tree, `vendor/libgphoto2` unless overridden by
+/// `LIBGPHOTO2_SRC_DIR`.
+fn source_dir() -> PathBuf {
+    let source = env_path("LIBGPHOTO2_SRC_DIR").unwrap_or_else(|| {
+        PathBuf::from(env::var_os("CARGO_MANIFEST_DIR").unwrap()).join("vendor/libgphoto2")
+    });
+
+    if !source.join("configure.ac").exists() {
+        panic!(
+            "the `vendored` feature needs the libgphoto2 sources in `{}`.\n\
+             Clone https://github.com/gphoto/libgphoto2 there, or point \
+             LIBGPHOTO2_SRC_DIR at an existing checkout.",
+            source.display()
+        );
+    }
+
+    source
+}
+
+/// The versioned driver directory (e.g. `lib/libgphoto2/2.5.31`) below `base`.
+fn driver_dir(base: &Path) -> PathBuf {
+    fs::read_dir(base)
+        .ok()
+        .and_then(|entries| {
+            entries
+                .filter_map(Result::ok)
+                .map(|entry| entry.path())
+                .find(|path| path.is_dir())
+        })
+        .unwrap_or_else(|| panic!("no drivers were installed into `{}`", base.display()))
+}
+
+/// The version in the `AC_INIT` line of the sources' `configure.ac`.
+fn source_version(source: &Path) -> Option<String> {
+    let configure = fs::read_to_string(source.join("configure.ac")).ok()?;
+    let init = configure
+        .lines()
+        .find(|line| line.trim_start().starts_with("AC_INIT("))?;
+
+    // AC_INIT([libgphoto2 photo camera library], [2.5.31], ...)
+    init.split('[')
+        .nth(2)?
+        .split(']')
+        .next()
+        .map(str::to_string)
+}
@@ -0,0 +1,103 @@
+use std::{env, fmt, str::FromStr};
+
+/// Releases that added bindings, exposed as a `cfg` and a cargo feature of
+/// the same name.
+///
+/// A `cfg` is set when the detected libgphoto2 is at least that version or
+/// when the feature is enabled, in which case an older library is an error.
+const VERSIONS: &[(&str, Version)] = &[
+    ("v2_5_10", Version(2, 5, 10)),
+    ("v2_5_20", Version(2, 5, 20)),
+    ("v2_5_27", Version(2, 5, 27)),
+];
+
+/// A libgphoto2 release, ignoring anything past the patch level.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub struct Version(u32, u32, u32);
+
+impl FromStr for Version {
+    type Err = String;
+
+    /// Parses versions like `2.5.31`, `2.5.27.1` or `2.5.32-dev`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.trim().split('.').map(|part| {
+            let digits = part
+                .find(|c: char| !c.is_ascii_digit())
+                .map_or(part, |end| &part[..end]);
+            digits.parse::<u32>()
+        });
+
+        match (parts.next(), parts.next(), parts.next()) {
+            (Some(Ok(major)), Some(Ok(minor)), patch) => Ok(Version(
+                major,
+                minor,
+                patch.and_then(Result::ok).unwrap_or(0),
+            )),
+            _ => Err(format!("`{}` is not a libgphoto2 version", s)),
+        }
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.0, self.1, self.2)
+    }
+}
+
+/// Emits the version `cfg`s for the libgphoto2 found by the build, returning
+/// the names of the enabled ones.
+///
+/// `LIBGPHOTO2_VERSION` overrides the detected version, which is unknown when
+/// the library is located through `LIBGPHOTO2_DIR` / `LIBGPHOTO2_LIB_DIR`.
+///
+/// With `runtime-load` both are ignored and only the features enable `cfg`s,
+/// as `load()` may open an older libgphoto2 than the build found, which would
+/// lack the functions of the newer releases.
+pub fn emit_cfgs(detected: Option<&str>) -> Vec<&'static str> {
+    let version = if cfg!(feature = "runtime-load") {
+        None
+    } else {
+        match env::var("LIBGPHOTO2_VERSION") {
+            Ok(version) => Some(version),
+            Err(_) => detected.map(str::to_string),
+        }
+    }
+    .map(|version| {
+        version
+            .parse::<Version>()
+            .unwrap_or_else(|err| panic!("{}", err))
+    });
+
+    if let Some(version) = version {
+        // Exposed to dependents as DEP_GPHOTO2_VERSION
+        println!("cargo:version={}", version);
+    }
+
+    let feature =
+        |cfg: &str| env::var_os(format!("CARGO_FEATURE_{}", cfg.to_uppercase())).is_some();
+
+    // Newer features enable the older ones, report the one that was asked for
+    if let (Some(version), Some(&(cfg, required))) =
+        (version, VERSIONS.iter().rev().find(|(cfg, _)| feature(cfg)))
+    {
+        if version < required {
+            panic!(
+                "the `{}` feature needs libgphoto2 {} or newer, but {} was found",
+                cfg, required, version
+            );
+        }
+    }
+
+    let mut enabled = Vec::new();
+
+    for &(cfg, required) in VERSIONS {
+        println!("cargo:rustc-check-cfg=cfg({})", cfg);
+
+        if feature(cfg) || version.is_some_and(|version| version >= required) {
+            println!("cargo:rustc-cfg={}", cfg);
+            enabled.push(cfg);
+        }
+    }
+
+    enabled
+}
@@ -0,0 +1,22 @@
+use std::env;
+
+/// The version `cfg`s libgphoto2-sys may set, see its `build/version.rs`.
+const VERSION_CFGS: &[&str] = &["v2_5_10", "v2_5_20", "v2_5_27"];
+
+fn main() {
+    for cfg in VERSION_CFGS {
+        println!("cargo:rustc-check-cfg=cfg({})", cfg);
+    }
+
+    // Use the same bindings libgphoto2-sys was built with
+    if let Ok(cfgs) = env::var("DEP_GPHOTO2_CFGS") {
+        for cfg in cfgs.split(',').filter(|cfg| !cfg.is_empty()) {
+            println!("cargo:rustc-cfg={}", cfg);
+        }
+    }
+
+    // The vendored libgphoto2 is linked dynamically from the build output
+    if let Ok(lib_dir) = env::var("DEP_GPHOTO2_LIB_DIR") {
+        println!("cargo:rustc-link-arg=-Wl,-rpath,{}", lib_dir);
+    }
+}
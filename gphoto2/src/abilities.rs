@@ -0,0 +1,389 @@
+//! Descriptions of camera models and what they support.
+
+use std::{fmt, iter::FusedIterator, mem, ptr, ptr::NonNull};
+
+use libc::c_int;
+use libgphoto2_sys::{
+    abilities_list::{
+        self, CameraAbilities, CameraAbilitiesList, CameraDriverStatus, CameraFileOperation,
+        CameraFolderOperation, CameraOperation, GphotoDeviceType,
+    },
+    port_info_list::GPPortType,
+};
+
+use crate::{
+    check,
+    context::Context,
+    util::{c_string, read_c_array, write_c_array, Indices},
+    GpError, Result,
+};
+
+/// The abilities of a camera model, see [`CameraAbilities`].
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Abilities {
+    /// Name of the camera model.
+    pub model: String,
+    /// Quality of the driver.
+    pub status: CameraDriverStatus,
+    /// Supported port types.
+    pub port: GPPortType,
+    /// Supported serial port speeds.
+    pub speeds: Vec<i32>,
+    /// Supported camera operations.
+    pub operations: CameraOperation,
+    /// Supported file operations.
+    pub file_operations: CameraFileOperation,
+    /// Supported folder operations.
+    pub folder_operations: CameraFolderOperation,
+    /// USB vendor id.
+    pub usb_vendor: i32,
+    /// USB product id.
+    pub usb_product: i32,
+    /// USB device class.
+    pub usb_class: i32,
+    /// USB device subclass.
+    pub usb_subclass: i32,
+    /// USB device protocol.
+    pub usb_protocol: i32,
+    /// File name of the camera driver, set by libgphoto2.
+    pub library: String,
+    /// Id of the camera driver, set by libgphoto2.
+    pub id: String,
+    /// Type of the device.
+    pub device_type: GphotoDeviceType,
+}
+
+impl Abilities {
+    /// A production quality still camera called `model` without any ports or
+    /// operations.
+    pub fn new(model: impl Into<String>) -> Self {
+        Abilities {
+            model: model.into(),
+            status: CameraDriverStatus::GP_DRIVER_STATUS_PRODUCTION,
+            port: GPPortType::GP_PORT_NONE,
+            speeds: Vec::new(),
+            operations: CameraOperation::GP_OPERATION_NONE,
+            file_operations: CameraFileOperation::GP_FILE_OPERATION_NONE,
+            folder_operations: CameraFolderOperation::GP_FOLDER_OPERATION_NONE,
+            usb_vendor: 0,
+            usb_product: 0,
+            usb_class: 0,
+            usb_subclass: 0,
+            usb_protocol: 0,
+            library: String::new(),
+            id: String::new(),
+            device_type: GphotoDeviceType::GP_DEVICE_STILL_CAMERA,
+        }
+    }
+
+    /// Copies the abilities out of the C struct.
+    pub fn from_raw(raw: &CameraAbilities) -> Self {
+        Abilities {
+            model: read_c_array(&raw.model),
+            status: raw.status,
+            port: raw.port,
+            speeds: raw
+                .speed
+                .iter()
+                .copied()
+                .take_while(|&speed| speed != 0)
+                .collect(),
+            operations: raw.operations,
+            file_operations: raw.file_operations,
+            folder_operations: raw.folder_operations,
+            usb_vendor: raw.usb_vendor,
+            usb_product: raw.usb_product,
+            usb_class: raw.usb_class,
+            usb_subclass: raw.usb_subclass,
+            usb_protocol: raw.usb_protocol,
+            library: read_c_array(&raw.library),
+            id: read_c_array(&raw.id),
+            device_type: raw.device_type,
+        }
+    }
+
+    /// The C struct for these abilities, strings and speeds that do not fit
+    /// are truncated.
+    pub fn to_raw(&self) -> CameraAbilities {
+        // All zeroes is valid for every field, including the private ones
+        let mut raw: CameraAbilities = unsafe { mem::zeroed() };
+
+        write_c_array(&mut raw.model, &self.model);
+        raw.status = self.status;
+        raw.port = self.port;
+        // The list of speeds is terminated with a 0
+        let speeds = raw.speed.len() - 1;
+        for (dst, &speed) in raw.speed.iter_mut().zip(self.speeds.iter().take(speeds)) {
+            *dst = speed as c_int;
+        }
+        raw.operations = self.operations;
+        raw.file_operations = self.file_operations;
+        raw.folder_operations = self.folder_operations;
+        raw.usb_vendor = self.usb_vendor;
+        raw.usb_product = self.usb_product;
+        raw.usb_class = self.usb_class;
+        raw.usb_subclass = self.usb_subclass;
+        raw.usb_protocol = self.usb_protocol;
+        write_c_array(&mut raw.library, &self.library);
+        write_c_array(&mut raw.id, &self.id);
+        raw.device_type = self.device_type;
+
+        raw
+    }
+}
+
+/// A list of camera models, e.g. every model supported by the installed
+/// camera drivers.
+pub struct AbilitiesList {
+    ptr: NonNull<CameraAbilitiesList>,
+}
+
+impl AbilitiesList {
+    /// Creates an empty list.
+    pub fn new() -> Result<Self> {
+        let mut ptr = ptr::null_mut();
+        check(unsafe { abilities_list::gp_abilities_list_new(&mut ptr) })?;
+
+        Ok(AbilitiesList {
+            ptr: NonNull::new(ptr).ok_or(GpError::NoMemory)?,
+        })
+    }
+
+    /// Loads the models supported by the camera drivers libgphoto2 finds.
+    pub fn load(context: &Context) -> Result<Self> {
+        let list = AbilitiesList::new()?;
+        check(unsafe { abilities_list::gp_abilities_list_load(list.as_ptr(), context.as_ptr()) })?;
+
+        Ok(list)
+    }
+
+    /// The wrapped `CameraAbilitiesList`.
+    pub fn as_ptr(&self) -> *mut CameraAbilitiesList {
+        self.ptr.as_ptr()
+    }
+
+    /// The number of models.
+    pub fn len(&self) -> usize {
+        check(unsafe { abilities_list::gp_abilities_list_count(self.as_ptr()) })
+            .map_or(0, |count| count as usize)
+    }
+
+    /// Returns whether the list has no models.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The abilities at `index`.
+    pub fn get(&self, index: usize) -> Option<Abilities> {
+        let index = c_int::try_from(index).ok()?;
+        self.raw(index).ok().map(|raw| Abilities::from_raw(&raw))
+    }
+
+    /// The abilities of the model called `model`, ignoring case.
+    ///
+    /// Fails with [`GpError::ModelNotFound`] if there is no such model.
+    pub fn lookup_model(&self, model: &str) -> Result<Abilities> {
+        let raw = self.raw(self.index_of(model)?)?;
+        Ok(Abilities::from_raw(&raw))
+    }
+
+    pub(crate) fn index_of(&self, model: &str) -> Result<c_int> {
+        let model = c_string(model)?;
+        check(unsafe {
+            abilities_list::gp_abilities_list_lookup_model(self.as_ptr(), model.as_ptr())
+        })
+    }
+
+    pub(crate) fn raw(&self, index: c_int) -> Result<CameraAbilities> {
+        let mut raw = unsafe { mem::zeroed() };
+        check(unsafe {
+            abilities_list::gp_abilities_list_get_abilities(self.as_ptr(), index, &mut raw)
+        })?;
+
+        Ok(raw)
+    }
+
+    /// Appends a model.
+    pub fn append(&mut self, abilities: &Abilities) -> Result<()> {
+        check(unsafe {
+            abilities_list::gp_abilities_list_append(self.as_ptr(), abilities.to_raw())
+        })?;
+        Ok(())
+    }
+
+    /// The models in the list.
+    pub fn iter(&self) -> Iter<'_> {
+        Iter {
+            list: self,
+            indices: Indices::new(self.len()),
+        }
+    }
+
+    /// The models matching `filter`.
+    pub fn filter<'a>(
+        &'a self,
+        filter: &'a AbilitiesFilter,
+    ) -> impl Iterator<Item = Abilities> + 'a {
+        self.iter().filter(|abilities| filter.matches(abilities))
+    }
+}
+
+impl Drop for AbilitiesList {
+    fn drop(&mut self) {
+        unsafe { abilities_list::gp_abilities_list_free(self.as_ptr()) };
+    }
+}
+
+impl fmt::Debug for AbilitiesList {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<'a> IntoIterator for &'a AbilitiesList {
+    type Item = Abilities;
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Iterator over the models of an [`AbilitiesList`].
+#[derive(Debug, Clone)]
+pub struct Iter<'a> {
+    list: &'a AbilitiesList,
+    indices: Indices,
+}
+
+impl Iterator for Iter<'_> {
+    type Item = Abilities;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.indices.next(|index| self.list.get(index))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.indices.size_hint()
+    }
+}
+
+impl ExactSizeIterator for Iter<'_> {}
+
+impl FusedIterator for Iter<'_> {}
+
+/// Criteria for selecting models from an [`AbilitiesList`], all of which
+/// must match.
+///
+/// ```ignore
+/// use gphoto2::{
+///     abilities::AbilitiesFilter,
+///     sys::{abilities_list::CameraOperation, port_info_list::GPPortType},
+/// };
+///
+/// // Cameras that can trigger a capture over USB
+/// let filter = AbilitiesFilter::new()
+///     .port(GPPortType::GP_PORT_USB)
+///     .operations(CameraOperation::GP_OPERATION_TRIGGER_CAPTURE);
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct AbilitiesFilter {
+    usb_vendor: Option<i32>,
+    usb_product: Option<i32>,
+    port: GPPortType,
+    status: Option<CameraDriverStatus>,
+    device_type: Option<GphotoDeviceType>,
+    operations: CameraOperation,
+    file_operations: CameraFileOperation,
+    folder_operations: CameraFolderOperation,
+}
+
+impl Default for AbilitiesFilter {
+    fn default() -> Self {
+        AbilitiesFilter {
+            usb_vendor: None,
+            usb_product: None,
+            port: GPPortType::GP_PORT_NONE,
+            status: None,
+            device_type: None,
+            operations: CameraOperation::GP_OPERATION_NONE,
+            file_operations: CameraFileOperation::GP_FILE_OPERATION_NONE,
+            folder_operations: CameraFolderOperation::GP_FOLDER_OPERATION_NONE,
+        }
+    }
+}
+
+impl AbilitiesFilter {
+    /// A filter matching every model.
+    pub fn new() -> Self {
+        AbilitiesFilter::default()
+    }
+
+    /// Models with the USB vendor id `vendor`.
+    pub fn usb_vendor(mut self, vendor: i32) -> Self {
+        self.usb_vendor = Some(vendor);
+        self
+    }
+
+    /// Models with the USB vendor id `vendor` and product id `product`.
+    pub fn usb_id(mut self, vendor: i32, product: i32) -> Self {
+        self.usb_vendor = Some(vendor);
+        self.usb_product = Some(product);
+        self
+    }
+
+    /// Models supporting any of the port types in `port`.
+    pub fn port(mut self, port: GPPortType) -> Self {
+        self.port = port;
+        self
+    }
+
+    /// Models whose driver has the status `status`.
+    pub fn status(mut self, status: CameraDriverStatus) -> Self {
+        self.status = Some(status);
+        self
+    }
+
+    /// Models of the device type `device_type`.
+    pub fn device_type(mut self, device_type: GphotoDeviceType) -> Self {
+        self.device_type = Some(device_type);
+        self
+    }
+
+    /// Models supporting all of `operations`.
+    pub fn operations(mut self, operations: CameraOperation) -> Self {
+        self.operations = operations;
+        self
+    }
+
+    /// Models supporting all of the file operations `operations`.
+    pub fn file_operations(mut self, operations: CameraFileOperation) -> Self {
+        self.file_operations = operations;
+        self
+    }
+
+    /// Models supporting all of the folder operations `operations`.
+    pub fn folder_operations(mut self, operations: CameraFolderOperation) -> Self {
+        self.folder_operations = operations;
+        self
+    }
+
+    /// Returns whether `abilities` match the filter.
+    pub fn matches(&self, abilities: &Abilities) -> bool {
+        self.usb_vendor
+            .map_or(true, |vendor| abilities.usb_vendor == vendor)
+            && self
+                .usb_product
+                .map_or(true, |product| abilities.usb_product == product)
+            && (self.port.is_empty() || abilities.port.intersects(self.port))
+            && self
+                .status
+                .map_or(true, |status| abilities.status == status)
+            && self
+                .device_type
+                .map_or(true, |device_type| abilities.device_type == device_type)
+            && abilities.operations.contains(self.operations)
+            && abilities.file_operations.contains(self.file_operations)
+            && abilities.folder_operations.contains(self.folder_operations)
+    }
+}
@@ -0,0 +1,253 @@
+//! Cameras attached to the system.
+
+use std::{
+    alloc::{self, Layout},
+    fmt, ptr,
+    ptr::NonNull,
+};
+
+use libc::c_int;
+use libgphoto2_sys::{
+    camera::{self, CameraText},
+    port_info_list::{self, GPPortType},
+};
+
+use crate::{
+    abilities::AbilitiesList,
+    check,
+    context::Context,
+    list::CameraList,
+    util::{c_string, read_c_array, Guard},
+    widget::Widget,
+    GpError, Result,
+};
+
+/// The address of a USB device.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub struct UsbAddress {
+    /// The number of the bus.
+    pub bus: u16,
+    /// The number of the device on the bus.
+    pub device: u16,
+}
+
+/// A camera found by [`autodetect`].
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub struct DetectedCamera {
+    /// The model name, e.g. `Canon EOS 5D Mark IV`.
+    pub model: String,
+    /// The path of the port, e.g. `usb:001,004`.
+    pub port: String,
+    /// The type of the port, from the prefix of its path.
+    pub port_type: GPPortType,
+    /// The USB address, if the path is of the form `usb:BBB,DDD`.
+    pub usb: Option<UsbAddress>,
+}
+
+impl DetectedCamera {
+    /// A camera of the model `model` at the port `port`.
+    pub fn new(model: &str, port: &str) -> Self {
+        DetectedCamera {
+            model: model.to_string(),
+            port: port.to_string(),
+            port_type: port_type(port),
+            usb: usb_address(port),
+        }
+    }
+}
+
+/// The type of the port at `path`, as libgphoto2_port names them.
+fn port_type(path: &str) -> GPPortType {
+    let prefix = path.split_once(':').map_or(path, |(prefix, _)| prefix);
+
+    match prefix {
+        "serial" => GPPortType::GP_PORT_SERIAL,
+        "usb" => GPPortType::GP_PORT_USB,
+        "disk" => GPPortType::GP_PORT_DISK,
+        "ptpip" => GPPortType::GP_PORT_PTPIP,
+        "usbdiskdirect" => GPPortType::GP_PORT_USB_DISK_DIRECT,
+        "usbscsi" => GPPortType::GP_PORT_USB_SCSI,
+        "ip" => GPPortType::GP_PORT_IP,
+        _ => GPPortType::GP_PORT_NONE,
+    }
+}
+
+/// The USB address in a path of the form `usb:BBB,DDD`.
+fn usb_address(path: &str) -> Option<UsbAddress> {
+    let (bus, device) = path.strip_prefix("usb:")?.split_once(',')?;
+
+    Some(UsbAddress {
+        bus: bus.parse().ok()?,
+        device: device.parse().ok()?,
+    })
+}
+
+/// Detects the cameras attached to the system.
+pub fn autodetect(context: &Context) -> Result<Vec<DetectedCamera>> {
+    let list = CameraList::new()?;
+    check(unsafe { camera::gp_camera_autodetect(list.as_ptr(), context.as_ptr()) })?;
+
+    Ok(list
+        .iter()
+        .map(|(model, port)| DetectedCamera::new(&model, port.as_deref().unwrap_or_default()))
+        .collect())
+}
+
+/// An initialized camera.
+///
+/// Clones refer to the same camera, which libgphoto2 exits when the last
+/// reference to it is released.
+pub struct Camera {
+    ptr: NonNull<camera::Camera>,
+}
+
+impl Camera {
+    /// Connects to the first camera libgphoto2 detects.
+    ///
+    /// Fails with [`GpError::ModelNotFound`] if no camera is attached.
+    pub fn new(context: &Context) -> Result<Self> {
+        let camera = Camera::alloc()?;
+        camera.init(context)?;
+
+        Ok(camera)
+    }
+
+    /// Connects to the camera of the model `model` at the port `port`, e.g.
+    /// one of the cameras returned by [`autodetect`].
+    ///
+    /// Unlike [`Camera::new`], this picks the right camera when several are
+    /// attached. Fails with [`GpError::ModelNotFound`] if no camera driver
+    /// supports `model` and with [`GpError::UnknownPort`] if no io library
+    /// provides `port`.
+    pub fn open(context: &Context, model: &str, port: &str) -> Result<Self> {
+        let port = c_string(port)?;
+
+        let abilities_list = AbilitiesList::load(context)?;
+        let abilities = abilities_list.raw(abilities_list.index_of(model)?)?;
+
+        let mut port_info_list = ptr::null_mut();
+        check(unsafe { port_info_list::gp_port_info_list_new(&mut port_info_list) })?;
+        let port_info_list = Guard::new(port_info_list, |list| unsafe {
+            port_info_list::gp_port_info_list_free(list);
+        });
+
+        let mut port_info = ptr::null_mut();
+        unsafe {
+            check(port_info_list::gp_port_info_list_load(port_info_list.ptr))?;
+            let index = check(port_info_list::gp_port_info_list_lookup_path(
+                port_info_list.ptr,
+                port.as_ptr(),
+            ))?;
+            check(port_info_list::gp_port_info_list_get_info(
+                port_info_list.ptr,
+                index,
+                &mut port_info,
+            ))?;
+        }
+
+        let camera = Camera::alloc()?;
+        unsafe {
+            // Both are copied into the camera
+            check(camera::gp_camera_set_abilities(camera.as_ptr(), abilities))?;
+            check(camera::gp_camera_set_port_info(camera.as_ptr(), port_info))?;
+        }
+        camera.init(context)?;
+
+        Ok(camera)
+    }
+
+    /// A camera that is not initialized yet.
+    pub(crate) fn alloc() -> Result<Self> {
+        let mut ptr = ptr::null_mut();
+        check(unsafe { camera::gp_camera_new(&mut ptr) })?;
+
+        Ok(Camera {
+            ptr: NonNull::new(ptr).ok_or(GpError::NoMemory)?,
+        })
+    }
+
+    /// Connects to the camera, detecting its model and port unless they
+    /// have been set.
+    pub(crate) fn init(&self, context: &Context) -> Result<()> {
+        check(unsafe { camera::gp_camera_init(self.as_ptr(), context.as_ptr()) })?;
+        Ok(())
+    }
+
+    /// The wrapped `Camera`.
+    pub fn as_ptr(&self) -> *mut camera::Camera {
+        self.ptr.as_ptr()
+    }
+
+    /// The configuration tree of the camera and its driver.
+    pub fn config(&self, context: &Context) -> Result<Widget> {
+        let mut window = ptr::null_mut();
+        check(unsafe {
+            camera::gp_camera_get_config(self.as_ptr(), &mut window, context.as_ptr())
+        })?;
+
+        // A driver without configuration fails rather than returning nothing
+        let window = NonNull::new(window).ok_or(GpError::Library)?;
+        Ok(unsafe { Widget::from_root(window) })
+    }
+
+    /// Applies the changed settings of the tree `config` belongs to, as
+    /// returned by [`Camera::config`].
+    pub fn set_config(&self, context: &Context, config: &Widget) -> Result<()> {
+        check(unsafe {
+            camera::gp_camera_set_config(self.as_ptr(), config.root().as_ptr(), context.as_ptr())
+        })?;
+        Ok(())
+    }
+
+    /// A summary of the camera and its state.
+    pub fn summary(&self, context: &Context) -> Result<String> {
+        text(|text| unsafe { camera::gp_camera_get_summary(self.as_ptr(), text, context.as_ptr()) })
+    }
+
+    /// The manual of the camera driver.
+    pub fn manual(&self, context: &Context) -> Result<String> {
+        text(|text| unsafe { camera::gp_camera_get_manual(self.as_ptr(), text, context.as_ptr()) })
+    }
+
+    /// Information about the camera driver and its authors.
+    pub fn about(&self, context: &Context) -> Result<String> {
+        text(|text| unsafe { camera::gp_camera_get_about(self.as_ptr(), text, context.as_ptr()) })
+    }
+}
+
+/// The text `f` writes into a `CameraText`.
+fn text(f: impl FnOnce(*mut CameraText) -> c_int) -> Result<String> {
+    // 32 KiB, too large for the stacks of many threads, so it is zeroed in
+    // place rather than moved into the box
+    let layout = Layout::new::<CameraText>();
+    let mut text = unsafe {
+        let ptr = alloc::alloc_zeroed(layout).cast::<CameraText>();
+        if ptr.is_null() {
+            alloc::handle_alloc_error(layout);
+        }
+        Box::from_raw(ptr)
+    };
+    check(f(&mut *text))?;
+
+    Ok(read_c_array(&text.text))
+}
+
+impl Clone for Camera {
+    fn clone(&self) -> Self {
+        unsafe { camera::gp_camera_ref(self.as_ptr()) };
+
+        Camera { ptr: self.ptr }
+    }
+}
+
+impl Drop for Camera {
+    fn drop(&mut self) {
+        unsafe { camera::gp_camera_unref(self.as_ptr()) };
+    }
+}
+
+impl fmt::Debug for Camera {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Camera").field("ptr", &self.ptr).finish()
+    }
+}
@@ -0,0 +1,382 @@
+//! Writing camera drivers (camlibs) in Rust.
+//!
+//! libgphoto2 loads every shared library in its camlib directory (`CAMLIBS`
+//! in the environment overrides it) and calls the `camera_id`,
+//! `camera_abilities` and `camera_init` functions it exports. Implement
+//! [`CameraDriver`] and invoke [`export_camlib!`](crate::export_camlib) in a
+//! `cdylib` crate to generate them:
+//!
+//! ```ignore
+//! use gphoto2::{abilities::Abilities, camlib::CameraDriver, port::Port, Result};
+//!
+//! struct Loopback {
+//!     port: Port,
+//! }
+//!
+//! impl CameraDriver for Loopback {
+//!     const ID: &'static str = "loopback";
+//!     const SUMMARY: bool = true;
+//!
+//!     fn abilities() -> Vec<Abilities> {
+//!         vec![Abilities::new("Loopback Camera")]
+//!     }
+//!
+//!     fn init(port: Port) -> Result<Self> {
+//!         Ok(Loopback { port })
+//!     }
+//!
+//!     fn summary(&mut self) -> Result<String> {
+//!         Ok("A camera that is not there".to_string())
+//!     }
+//! }
+//!
+//! gphoto2::export_camlib!(Loopback);
+//! ```
+
+use std::{ptr, ptr::NonNull};
+
+use libc::{c_char, c_int, c_ulong};
+use libgphoto2_sys::{
+    abilities_list::{self, CameraAbilitiesList},
+    camera::{Camera, CameraCaptureType, CameraFilePath, CameraPrivateLibrary, CameraText},
+    context::GPContext,
+    file::{self, CameraFile},
+    result::GP_OK,
+    widget::CameraWidget,
+};
+
+use crate::{
+    abilities::Abilities,
+    check,
+    port::Port,
+    util::{ffi_guard, write_c_array},
+    widget::Widget,
+    GpError, Result,
+};
+
+/// The location of a file on the camera.
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub struct FilePath {
+    /// The folder containing the file.
+    pub folder: String,
+    /// The name of the file.
+    pub name: String,
+}
+
+/// A preview image captured by [`CameraDriver::capture_preview`].
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Preview {
+    /// The encoded image.
+    pub data: Vec<u8>,
+    /// The MIME type of `data`, e.g. `image/jpeg`.
+    pub mime_type: String,
+}
+
+/// A camera driver.
+///
+/// A driver is created by [`CameraDriver::init`] when a frontend initializes
+/// a camera it supports and dropped after [`CameraDriver::exit`].
+///
+/// libgphoto2 only calls the optional operations whose flag is set, e.g.
+/// [`CameraDriver::SUMMARY`] for [`CameraDriver::summary`], and reports the
+/// others as unsupported itself.
+pub trait CameraDriver: Sized + 'static {
+    /// The unique id of the driver.
+    const ID: &'static str;
+
+    /// Whether the driver implements [`CameraDriver::get_config`].
+    const GET_CONFIG: bool = false;
+    /// Whether the driver implements [`CameraDriver::set_config`].
+    const SET_CONFIG: bool = false;
+    /// Whether the driver implements [`CameraDriver::capture`].
+    const CAPTURE: bool = false;
+    /// Whether the driver implements [`CameraDriver::capture_preview`].
+    const CAPTURE_PREVIEW: bool = false;
+    /// Whether the driver implements [`CameraDriver::summary`].
+    const SUMMARY: bool = false;
+    /// Whether the driver implements [`CameraDriver::manual`].
+    const MANUAL: bool = false;
+    /// Whether the driver implements [`CameraDriver::about`].
+    const ABOUT: bool = false;
+
+    /// The camera models the driver supports.
+    fn abilities() -> Vec<Abilities>;
+
+    /// Connects to the camera on `port`, which libgphoto2 has already opened.
+    fn init(port: Port) -> Result<Self>;
+
+    /// Called before the camera is closed.
+    fn exit(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    /// Builds the configuration widget tree of the camera.
+    ///
+    /// The returned widget must be the root of its tree, without other
+    /// handles into it, as the tree is handed over to libgphoto2.
+    fn get_config(&mut self) -> Result<Widget> {
+        Err(GpError::NotSupported)
+    }
+
+    /// Applies the changed values of a widget tree returned by
+    /// [`CameraDriver::get_config`].
+    ///
+    /// The tree still belongs to libgphoto2, so appending it or its root to
+    /// another tree fails with [`GpError::BadParameters`].
+    fn set_config(&mut self, _config: &Widget) -> Result<()> {
+        Err(GpError::NotSupported)
+    }
+
+    /// Captures an image, movie or sound, returning where it is stored.
+    fn capture(&mut self, _capture_type: CameraCaptureType) -> Result<FilePath> {
+        Err(GpError::NotSupported)
+    }
+
+    /// Captures a preview image.
+    fn capture_preview(&mut self) -> Result<Preview> {
+        Err(GpError::NotSupported)
+    }
+
+    /// A summary of the camera and its state.
+    fn summary(&mut self) -> Result<String> {
+        Err(GpError::NotSupported)
+    }
+
+    /// The manual of the driver.
+    fn manual(&mut self) -> Result<String> {
+        Err(GpError::NotSupported)
+    }
+
+    /// Information about the driver and its authors.
+    fn about(&mut self) -> Result<String> {
+        Err(GpError::NotSupported)
+    }
+}
+
+/// Exports the camlib entry points for a [`CameraDriver`].
+///
+/// Generates `camera_id`, `camera_abilities` and `camera_init`, so it may be
+/// used only once per library. The crate must be built as a `cdylib`.
+#[macro_export]
+macro_rules! export_camlib {
+    ($driver:ty) => {
+        #[no_mangle]
+        pub extern "C" fn camera_id(
+            id: *mut $crate::sys::camera::CameraText,
+        ) -> ::std::os::raw::c_int {
+            unsafe { $crate::camlib::camera_id::<$driver>(id) }
+        }
+
+        #[no_mangle]
+        pub extern "C" fn camera_abilities(
+            list: *mut $crate::sys::abilities_list::CameraAbilitiesList,
+        ) -> ::std::os::raw::c_int {
+            unsafe { $crate::camlib::camera_abilities::<$driver>(list) }
+        }
+
+        #[no_mangle]
+        pub extern "C" fn camera_init(
+            camera: *mut $crate::sys::camera::Camera,
+            context: *mut $crate::sys::context::GPContext,
+        ) -> ::std::os::raw::c_int {
+            unsafe { $crate::camlib::camera_init::<$driver>(camera, context) }
+        }
+    };
+}
+
+/// Runs `f`, turning its result or a panic into a gphoto2 return value.
+fn guard(f: impl FnOnce() -> Result<()>) -> c_int {
+    ffi_guard(|| f().map(|()| GP_OK))
+}
+
+/// The driver stored in the private data of `camera` by `camera_init`.
+///
+/// # Safety
+///
+/// `camera` must have been initialized by `camera_init::<D>` and not exited.
+unsafe fn driver<'a, D: CameraDriver>(camera: *mut Camera) -> Result<&'a mut D> {
+    let driver = (*camera).pl as *mut D;
+
+    driver.as_mut().ok_or(GpError::BadParameters)
+}
+
+/// Implementation of `camera_id` for [`export_camlib!`](crate::export_camlib).
+///
+/// # Safety
+///
+/// `id` must point to a valid `CameraText`.
+#[doc(hidden)]
+pub unsafe fn camera_id<D: CameraDriver>(id: *mut CameraText) -> c_int {
+    guard(|| {
+        write_c_array(&mut (*id).text, D::ID);
+        Ok(())
+    })
+}
+
+/// Implementation of `camera_abilities` for [`export_camlib!`](crate::export_camlib).
+///
+/// # Safety
+///
+/// `list` must point to a valid abilities list.
+#[doc(hidden)]
+pub unsafe fn camera_abilities<D: CameraDriver>(list: *mut CameraAbilitiesList) -> c_int {
+    guard(|| {
+        for abilities in D::abilities() {
+            check(abilities_list::gp_abilities_list_append(
+                list,
+                abilities.to_raw(),
+            ))?;
+        }
+        Ok(())
+    })
+}
+
+/// Implementation of `camera_init` for [`export_camlib!`](crate::export_camlib).
+///
+/// # Safety
+///
+/// `camera` must point to a valid camera with a function table.
+#[doc(hidden)]
+pub unsafe fn camera_init<D: CameraDriver>(camera: *mut Camera, _context: *mut GPContext) -> c_int {
+    guard(|| {
+        let driver = D::init(Port::from_raw((*camera).port))?;
+        (*camera).pl = Box::into_raw(Box::new(driver)) as *mut CameraPrivateLibrary;
+
+        let functions = &mut *(*camera).functions;
+        functions.exit = Some(exit::<D>);
+        if D::GET_CONFIG {
+            functions.get_config = Some(get_config::<D>);
+        }
+        if D::SET_CONFIG {
+            functions.set_config = Some(set_config::<D>);
+        }
+        if D::CAPTURE {
+            functions.capture = Some(capture::<D>);
+        }
+        if D::CAPTURE_PREVIEW {
+            functions.capture_preview = Some(capture_preview::<D>);
+        }
+        if D::SUMMARY {
+            functions.summary = Some(summary::<D>);
+        }
+        if D::MANUAL {
+            functions.manual = Some(manual::<D>);
+        }
+        if D::ABOUT {
+            functions.about = Some(about::<D>);
+        }
+
+        Ok(())
+    })
+}
+
+extern "C" fn exit<D: CameraDriver>(camera: *mut Camera, _context: *mut GPContext) -> c_int {
+    guard(|| unsafe {
+        let driver = (*camera).pl as *mut D;
+        if driver.is_null() {
+            return Ok(());
+        }
+
+        (*camera).pl = ptr::null_mut();
+        Box::from_raw(driver).exit()
+    })
+}
+
+extern "C" fn get_config<D: CameraDriver>(
+    camera: *mut Camera,
+    widget: *mut *mut CameraWidget,
+    _context: *mut GPContext,
+) -> c_int {
+    guard(|| unsafe {
+        *widget = driver::<D>(camera)?.get_config()?.into_root()?.as_ptr();
+        Ok(())
+    })
+}
+
+extern "C" fn set_config<D: CameraDriver>(
+    camera: *mut Camera,
+    widget: *mut CameraWidget,
+    _context: *mut GPContext,
+) -> c_int {
+    guard(|| unsafe {
+        let widget = NonNull::new(widget).ok_or(GpError::BadParameters)?;
+        driver::<D>(camera)?.set_config(&Widget::from_borrowed(widget))
+    })
+}
+
+extern "C" fn capture<D: CameraDriver>(
+    camera: *mut Camera,
+    capture_type: CameraCaptureType,
+    path: *mut CameraFilePath,
+    _context: *mut GPContext,
+) -> c_int {
+    guard(|| unsafe {
+        let captured = driver::<D>(camera)?.capture(capture_type)?;
+
+        write_c_array(&mut (*path).folder, &captured.folder);
+        write_c_array(&mut (*path).name, &captured.name);
+        Ok(())
+    })
+}
+
+extern "C" fn capture_preview<D: CameraDriver>(
+    camera: *mut Camera,
+    file: *mut CameraFile,
+    _context: *mut GPContext,
+) -> c_int {
+    guard(|| unsafe {
+        let preview = driver::<D>(camera)?.capture_preview()?;
+
+        let mut mime_type = preview.mime_type.into_bytes();
+        mime_type.push(0);
+
+        check(file::gp_file_append(
+            file,
+            preview.data.as_ptr() as *const c_char,
+            preview.data.len() as c_ulong,
+        ))?;
+        check(file::gp_file_set_mime_type(
+            file,
+            mime_type.as_ptr() as *const c_char,
+        ))?;
+        Ok(())
+    })
+}
+
+/// Writes the text returned by `f` into `text`.
+fn text<D: CameraDriver>(
+    camera: *mut Camera,
+    text: *mut CameraText,
+    f: fn(&mut D) -> Result<String>,
+) -> c_int {
+    guard(|| unsafe {
+        let driver_text = f(driver::<D>(camera)?)?;
+
+        write_c_array(&mut (*text).text, &driver_text);
+        Ok(())
+    })
+}
+
+extern "C" fn summary<D: CameraDriver>(
+    camera: *mut Camera,
+    summary: *mut CameraText,
+    _context: *mut GPContext,
+) -> c_int {
+    text(camera, summary, D::summary)
+}
+
+extern "C" fn manual<D: CameraDriver>(
+    camera: *mut Camera,
+    manual: *mut CameraText,
+    _context: *mut GPContext,
+) -> c_int {
+    text(camera, manual, D::manual)
+}
+
+extern "C" fn about<D: CameraDriver>(
+    camera: *mut Camera,
+    about: *mut CameraText,
+    _context: *mut GPContext,
+) -> c_int {
+    text(camera, about, D::about)
+}
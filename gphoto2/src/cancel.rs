@@ -0,0 +1,87 @@
+//! Cancelling operations from other threads.
+//!
+//! A [`CancellationToken`] registered with
+//! [`Context::with_cancellation`](crate::context::Context::with_cancellation)
+//! is polled by libgphoto2 during long operations such as downloads. Once it
+//! is cancelled, the operation stops and fails with [`GpError::Cancel`],
+//! which [`Outcome::from_result`] turns into [`Outcome::Cancelled`]:
+//!
+//! ```ignore
+//! use gphoto2::{cancel::{CancellationToken, Outcome}, context::Context};
+//!
+//! let token = CancellationToken::new();
+//! let context = Context::new()?.with_cancellation(token.clone());
+//!
+//! // From the UI thread
+//! token.cancel();
+//!
+//! match Outcome::from_result(download(&context))? {
+//!     Outcome::Completed(file) => save(file),
+//!     Outcome::Cancelled => {}
+//! }
+//! ```
+
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+use crate::{GpError, Result};
+
+/// A flag requesting the cancellation of operations, shared by its clones.
+#[derive(Debug, Default, Clone)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    /// Creates a token that is not cancelled.
+    pub fn new() -> Self {
+        CancellationToken::default()
+    }
+
+    /// Requests the cancellation of the operations of every context the
+    /// token or one of its clones is registered with.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns whether [`CancellationToken::cancel`] has been called.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+/// The outcome of an operation that may be cancelled.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Outcome<T> {
+    /// The operation finished.
+    Completed(T),
+    /// The operation was cancelled.
+    Cancelled,
+}
+
+impl<T> Outcome<T> {
+    /// Turns the [`GpError::Cancel`] error of `result` into
+    /// [`Outcome::Cancelled`], keeping other errors.
+    pub fn from_result(result: Result<T>) -> Result<Self> {
+        match result {
+            Ok(value) => Ok(Outcome::Completed(value)),
+            Err(GpError::Cancel) => Ok(Outcome::Cancelled),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Returns whether the operation was cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        matches!(self, Outcome::Cancelled)
+    }
+
+    /// The result of the operation, if it finished.
+    pub fn completed(self) -> Option<T> {
+        match self {
+            Outcome::Completed(value) => Some(value),
+            Outcome::Cancelled => None,
+        }
+    }
+}
@@ -0,0 +1,305 @@
+//! Contexts passed to libgphoto2 operations.
+//!
+//! A [`Context`] receives the errors, status messages, questions and
+//! progress of the operations it is passed to, and is asked whether they
+//! should be cancelled. Each callback is a Rust closure registered with a
+//! builder method:
+//!
+//! ```ignore
+//! use gphoto2::context::Context;
+//!
+//! let mut errors = Vec::new();
+//! let context = Context::new()?
+//!     .on_error(|text| errors.push(text.to_string()))
+//!     .on_status(|text| println!("{text}"));
+//! ```
+
+use std::{borrow::Cow, cell::RefCell, ffi::CStr, fmt, ptr::NonNull, rc::Rc};
+
+use libc::{c_char, c_float, c_uint, c_void};
+use libgphoto2_sys::context::{self, GPContext, GPContextFeedback};
+
+use crate::{
+    cancel::CancellationToken,
+    progress::{ProgressEvent, ProgressTask, ProgressTracker},
+    util::catch_panic,
+    GpError, Result,
+};
+
+/// A callback, `None` until it is registered.
+type Slot<F> = RefCell<Option<Box<F>>>;
+
+/// A callback receiving the progress target and description.
+type ProgressStart<'a> = dyn FnMut(f32, &str) -> u32 + 'a;
+
+/// The closures registered with a context, shared by all its clones.
+#[derive(Default)]
+struct Callbacks<'a> {
+    idle: Slot<dyn FnMut() + 'a>,
+    error: Slot<dyn FnMut(&str) + 'a>,
+    status: Slot<dyn FnMut(&str) + 'a>,
+    message: Slot<dyn FnMut(&str) + 'a>,
+    question: Slot<dyn FnMut(&str) -> GPContextFeedback + 'a>,
+    cancel: Slot<dyn FnMut() -> GPContextFeedback + 'a>,
+    progress_start: Slot<ProgressStart<'a>>,
+    progress_update: Slot<dyn FnMut(u32, f32) + 'a>,
+    progress_stop: Slot<dyn FnMut(u32) + 'a>,
+}
+
+/// A `GPContext` whose callbacks are Rust closures.
+///
+/// Clones refer to the same context and share its callbacks, which are
+/// dropped with the last clone. The lifetime `'a` bounds the closures, so
+/// they may borrow from the caller.
+pub struct Context<'a> {
+    ptr: NonNull<GPContext>,
+    callbacks: Rc<Callbacks<'a>>,
+}
+
+impl<'a> Context<'a> {
+    /// Creates a context without callbacks.
+    pub fn new() -> Result<Self> {
+        let ptr = NonNull::new(unsafe { context::gp_context_new() }).ok_or(GpError::NoMemory)?;
+
+        Ok(Context {
+            ptr,
+            callbacks: Rc::default(),
+        })
+    }
+
+    /// The wrapped `GPContext`.
+    ///
+    /// The callbacks of the context may only be called while a clone of the
+    /// `Context` is alive.
+    pub fn as_ptr(&self) -> *mut GPContext {
+        self.ptr.as_ptr()
+    }
+
+    /// The pointer passed to the trampolines as their data.
+    fn data(&self) -> *mut c_void {
+        Rc::as_ptr(&self.callbacks) as *mut c_void
+    }
+
+    /// Calls `f` while libgphoto2 is busy, e.g. to keep a UI responsive.
+    pub fn on_idle(self, f: impl FnMut() + 'a) -> Self {
+        *self.callbacks.idle.borrow_mut() = Some(Box::new(f));
+        unsafe { context::gp_context_set_idle_func(self.as_ptr(), idle, self.data()) };
+        self
+    }
+
+    /// Calls `f` with the error messages of failed operations.
+    pub fn on_error(self, f: impl FnMut(&str) + 'a) -> Self {
+        *self.callbacks.error.borrow_mut() = Some(Box::new(f));
+        unsafe { context::gp_context_set_error_func(self.as_ptr(), error, self.data()) };
+        self
+    }
+
+    /// Calls `f` with status messages, e.g. the file being downloaded.
+    pub fn on_status(self, f: impl FnMut(&str) + 'a) -> Self {
+        *self.callbacks.status.borrow_mut() = Some(Box::new(f));
+        unsafe { context::gp_context_set_status_func(self.as_ptr(), status, self.data()) };
+        self
+    }
+
+    /// Calls `f` with messages for the user.
+    pub fn on_message(self, f: impl FnMut(&str) + 'a) -> Self {
+        *self.callbacks.message.borrow_mut() = Some(Box::new(f));
+        unsafe { context::gp_context_set_message_func(self.as_ptr(), message, self.data()) };
+        self
+    }
+
+    /// Asks `f` questions for the user, the operation is cancelled if it
+    /// returns `GP_CONTEXT_FEEDBACK_CANCEL`.
+    pub fn on_question(self, f: impl FnMut(&str) -> GPContextFeedback + 'a) -> Self {
+        *self.callbacks.question.borrow_mut() = Some(Box::new(f));
+        unsafe { context::gp_context_set_question_func(self.as_ptr(), question, self.data()) };
+        self
+    }
+
+    /// Asks `f` whether the running operation should be cancelled.
+    pub fn on_cancel(self, f: impl FnMut() -> GPContextFeedback + 'a) -> Self {
+        *self.callbacks.cancel.borrow_mut() = Some(Box::new(f));
+        unsafe { context::gp_context_set_cancel_func(self.as_ptr(), cancel, self.data()) };
+        self
+    }
+
+    /// Cancels the running operation once `token` is cancelled.
+    ///
+    /// Replaces the callback registered with [`Context::on_cancel`].
+    pub fn with_cancellation(self, token: CancellationToken) -> Self {
+        self.on_cancel(move || {
+            if token.is_cancelled() {
+                GPContextFeedback::GP_CONTEXT_FEEDBACK_CANCEL
+            } else {
+                GPContextFeedback::GP_CONTEXT_FEEDBACK_OK
+            }
+        })
+    }
+
+    /// Reports the progress of operations.
+    ///
+    /// `start` is called with the target value and a description when an
+    /// operation starts and returns an id for it, `update` with the id and
+    /// the current value and `stop` with the id when the operation is done.
+    pub fn on_progress(
+        self,
+        start: impl FnMut(f32, &str) -> u32 + 'a,
+        update: impl FnMut(u32, f32) + 'a,
+        stop: impl FnMut(u32) + 'a,
+    ) -> Self {
+        *self.callbacks.progress_start.borrow_mut() = Some(Box::new(start));
+        *self.callbacks.progress_update.borrow_mut() = Some(Box::new(update));
+        *self.callbacks.progress_stop.borrow_mut() = Some(Box::new(stop));
+        unsafe {
+            context::gp_context_set_progress_funcs(
+                self.as_ptr(),
+                progress_start,
+                progress_update,
+                progress_stop,
+                self.data(),
+            )
+        };
+        self
+    }
+
+    /// Calls `f` with the progress events of operations and the state of the
+    /// operation after the event, tracked by a [`ProgressTracker`].
+    ///
+    /// Replaces the callbacks registered with [`Context::on_progress`].
+    pub fn on_progress_events(self, f: impl FnMut(&ProgressEvent, &ProgressTask) + 'a) -> Self {
+        let state = Rc::new(RefCell::new((ProgressTracker::new(), f)));
+        let (update_state, stop_state) = (Rc::clone(&state), Rc::clone(&state));
+
+        self.on_progress(
+            move |target, text| {
+                let (tracker, f) = &mut *state.borrow_mut();
+                let (event, task) = tracker.start(target, text);
+                f(&event, task);
+                task.id
+            },
+            move |id, current| {
+                let (tracker, f) = &mut *update_state.borrow_mut();
+                if let Some((event, task)) = tracker.update(id, current) {
+                    f(&event, task);
+                }
+            },
+            move |id| {
+                let (tracker, f) = &mut *stop_state.borrow_mut();
+                if let Some((event, task)) = tracker.stop(id) {
+                    f(&event, &task);
+                }
+            },
+        )
+    }
+}
+
+impl Clone for Context<'_> {
+    fn clone(&self) -> Self {
+        unsafe { context::gp_context_ref(self.as_ptr()) };
+
+        Context {
+            ptr: self.ptr,
+            callbacks: Rc::clone(&self.callbacks),
+        }
+    }
+}
+
+impl Drop for Context<'_> {
+    fn drop(&mut self) {
+        unsafe { context::gp_context_unref(self.as_ptr()) };
+    }
+}
+
+impl fmt::Debug for Context<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Context").field("ptr", &self.ptr).finish()
+    }
+}
+
+/// Calls the closure in `slot`, if it is registered.
+///
+/// Returns `None` without calling it if the closure panics or is already
+/// running, as a callback that calls back into libgphoto2 with the same
+/// context may be invoked again.
+///
+/// # Safety
+///
+/// `data` must be the data pointer of a live [`Context`].
+unsafe fn call<F: ?Sized + 'static, R>(
+    data: *mut c_void,
+    slot: impl FnOnce(&'static Callbacks<'static>) -> &'static Slot<F>,
+    f: impl FnOnce(&mut F) -> R,
+) -> Option<R> {
+    let callbacks = &*(data as *const Callbacks<'static>);
+
+    catch_panic(|| {
+        let mut callback = slot(callbacks).try_borrow_mut().ok()?;
+        callback.as_mut().map(|callback| f(callback))
+    })
+    .flatten()
+}
+
+/// The text passed to a callback.
+///
+/// # Safety
+///
+/// `text` must be null or point to a NUL terminated string.
+unsafe fn text<'b>(text: *const c_char) -> Cow<'b, str> {
+    if text.is_null() {
+        "".into()
+    } else {
+        CStr::from_ptr(text).to_string_lossy()
+    }
+}
+
+extern "C" fn idle(_context: *mut GPContext, data: *mut c_void) {
+    unsafe { call(data, |c| &c.idle, |f| f()) };
+}
+
+extern "C" fn error(_context: *mut GPContext, message: *const c_char, data: *mut c_void) {
+    unsafe { call(data, |c| &c.error, |f| f(&text(message))) };
+}
+
+extern "C" fn status(_context: *mut GPContext, message: *const c_char, data: *mut c_void) {
+    unsafe { call(data, |c| &c.status, |f| f(&text(message))) };
+}
+
+extern "C" fn message(_context: *mut GPContext, message: *const c_char, data: *mut c_void) {
+    unsafe { call(data, |c| &c.message, |f| f(&text(message))) };
+}
+
+extern "C" fn question(
+    _context: *mut GPContext,
+    message: *const c_char,
+    data: *mut c_void,
+) -> GPContextFeedback {
+    unsafe { call(data, |c| &c.question, |f| f(&text(message))) }
+        .unwrap_or(GPContextFeedback::GP_CONTEXT_FEEDBACK_OK)
+}
+
+extern "C" fn cancel(_context: *mut GPContext, data: *mut c_void) -> GPContextFeedback {
+    unsafe { call(data, |c| &c.cancel, |f| f()) }
+        .unwrap_or(GPContextFeedback::GP_CONTEXT_FEEDBACK_OK)
+}
+
+extern "C" fn progress_start(
+    _context: *mut GPContext,
+    target: c_float,
+    message: *const c_char,
+    data: *mut c_void,
+) -> c_uint {
+    unsafe { call(data, |c| &c.progress_start, |f| f(target, &text(message))) }.unwrap_or(0)
+}
+
+extern "C" fn progress_update(
+    _context: *mut GPContext,
+    id: c_uint,
+    current: c_float,
+    data: *mut c_void,
+) {
+    unsafe { call(data, |c| &c.progress_update, |f| f(id, current)) };
+}
+
+extern "C" fn progress_stop(_context: *mut GPContext, id: c_uint, data: *mut c_void) {
+    unsafe { call(data, |c| &c.progress_stop, |f| f(id)) };
+}
@@ -0,0 +1,159 @@
+//! Errors returned by libgphoto2.
+
+use std::{error::Error, fmt};
+
+use libc::c_int;
+use libgphoto2_sys::result;
+
+use crate::util::read_c_str;
+
+/// Result of a libgphoto2 operation.
+pub type Result<T, E = GpError> = std::result::Result<T, E>;
+
+/// Declares `GpError` with a variant per `GP_ERROR_*` code.
+macro_rules! gp_errors {
+    ($(
+        $(#[doc = $doc:literal])*
+        $(#[cfg($cfg:meta)])?
+        $variant:ident = $code:path,
+    )*) => {
+        /// An error code returned by libgphoto2 or libgphoto2_port.
+        #[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+        #[non_exhaustive]
+        pub enum GpError {
+            $(
+                $(#[doc = $doc])*
+                $(#[cfg($cfg)])?
+                $variant,
+            )*
+            /// A negative code these bindings do not know about.
+            Unknown(i32),
+        }
+
+        impl GpError {
+            /// The error for the negative return value `code`.
+            pub fn from_code(code: c_int) -> Self {
+                match code {
+                    $(
+                        $(#[cfg($cfg)])?
+                        $code => GpError::$variant,
+                    )*
+                    code => GpError::Unknown(code),
+                }
+            }
+
+            /// The `GP_ERROR_*` value of this error.
+            pub fn code(&self) -> c_int {
+                match *self {
+                    $(
+                        $(#[cfg($cfg)])?
+                        GpError::$variant => $code,
+                    )*
+                    GpError::Unknown(code) => code,
+                }
+            }
+        }
+    };
+}
+
+gp_errors! {
+    /// Generic error.
+    Generic = result::GP_ERROR,
+    /// Bad parameters passed.
+    BadParameters = result::GP_ERROR_BAD_PARAMETERS,
+    /// Out of memory.
+    NoMemory = result::GP_ERROR_NO_MEMORY,
+    /// Error in the camera driver.
+    Library = result::GP_ERROR_LIBRARY,
+    /// Unknown libgphoto2 port passed.
+    UnknownPort = result::GP_ERROR_UNKNOWN_PORT,
+    /// Functionality not supported.
+    NotSupported = result::GP_ERROR_NOT_SUPPORTED,
+    /// Generic I/O error.
+    Io = result::GP_ERROR_IO,
+    /// Buffer overflow of internal structure.
+    FixedLimitExceeded = result::GP_ERROR_FIXED_LIMIT_EXCEEDED,
+    /// Operation timed out.
+    Timeout = result::GP_ERROR_TIMEOUT,
+    /// Serial ports not supported.
+    IoSupportedSerial = result::GP_ERROR_IO_SUPPORTED_SERIAL,
+    /// USB ports not supported.
+    IoSupportedUsb = result::GP_ERROR_IO_SUPPORTED_USB,
+    /// Error initialising I/O.
+    IoInit = result::GP_ERROR_IO_INIT,
+    /// I/O during read.
+    IoRead = result::GP_ERROR_IO_READ,
+    /// I/O during write.
+    IoWrite = result::GP_ERROR_IO_WRITE,
+    /// I/O during update of settings.
+    IoUpdate = result::GP_ERROR_IO_UPDATE,
+    /// Specified serial speed not possible.
+    IoSerialSpeed = result::GP_ERROR_IO_SERIAL_SPEED,
+    /// Error clearing a halt condition on the USB device.
+    IoUsbClearHalt = result::GP_ERROR_IO_USB_CLEAR_HALT,
+    /// Could not find the requested USB device.
+    IoUsbFind = result::GP_ERROR_IO_USB_FIND,
+    /// Could not claim the USB device.
+    IoUsbClaim = result::GP_ERROR_IO_USB_CLAIM,
+    /// Could not lock the device.
+    IoLock = result::GP_ERROR_IO_LOCK,
+    /// libhal error.
+    Hal = result::GP_ERROR_HAL,
+    /// Corrupted data received.
+    CorruptedData = result::GP_ERROR_CORRUPTED_DATA,
+    /// File already exists.
+    FileExists = result::GP_ERROR_FILE_EXISTS,
+    /// Specified camera model was not found.
+    ModelNotFound = result::GP_ERROR_MODEL_NOT_FOUND,
+    /// Specified directory was not found.
+    DirectoryNotFound = result::GP_ERROR_DIRECTORY_NOT_FOUND,
+    /// Specified file was not found.
+    FileNotFound = result::GP_ERROR_FILE_NOT_FOUND,
+    /// Specified directory already exists.
+    DirectoryExists = result::GP_ERROR_DIRECTORY_EXISTS,
+    /// The camera is already busy.
+    CameraBusy = result::GP_ERROR_CAMERA_BUSY,
+    /// Path is not absolute.
+    PathNotAbsolute = result::GP_ERROR_PATH_NOT_ABSOLUTE,
+    /// Cancellation successful.
+    Cancel = result::GP_ERROR_CANCEL,
+    /// Unspecified camera error.
+    CameraError = result::GP_ERROR_CAMERA_ERROR,
+    /// Unspecified failure of the operating system.
+    OsFailure = result::GP_ERROR_OS_FAILURE,
+    /// Not enough space when uploading a file.
+    #[cfg(v2_5_27)]
+    NoSpace = result::GP_ERROR_NO_SPACE,
+}
+
+impl fmt::Display for GpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (result_as_string(self.code()), self) {
+            (Some(text), GpError::Unknown(code)) => write!(f, "{} ({})", text, code),
+            (Some(text), _) => f.write_str(&text),
+            (None, _) => write!(f, "libgphoto2 error {}", self.code()),
+        }
+    }
+}
+
+impl Error for GpError {}
+
+/// The description libgphoto2 has for `code`.
+fn result_as_string(code: c_int) -> Option<String> {
+    #[cfg(feature = "runtime-load")]
+    if !libgphoto2_sys::is_loaded() {
+        return None;
+    }
+
+    unsafe { read_c_str(result::gp_result_as_string(code)) }
+}
+
+/// Turns the return value of a libgphoto2 function into a `Result`,
+/// keeping non-negative values (counts, indices, ids).
+pub fn check(ret: c_int) -> Result<i32> {
+    if ret < 0 {
+        Err(GpError::from_code(ret))
+    } else {
+        Ok(ret)
+    }
+}
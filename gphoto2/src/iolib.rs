@@ -0,0 +1,316 @@
+//! Writing io libraries (iolibs) in Rust.
+//!
+//! libgphoto2_port loads every shared library in its iolib directory
+//! (`IOLIBS` in the environment overrides it) and calls the
+//! `gp_port_library_type`, `gp_port_library_list` and
+//! `gp_port_library_operations` functions it exports. Implement
+//! [`PortDriver`] and invoke [`export_iolib!`](crate::export_iolib) in a
+//! `cdylib` crate to generate them, e.g. for a transport that replays
+//! scripted byte streams to test a camera driver without a device.
+
+use std::{mem, ptr, slice};
+
+use libc::{c_char, c_int};
+use libgphoto2_sys::{
+    port::{GPPort, GPPortSettings},
+    port_info_list::{self, GPPortInfo, GPPortInfoList, GPPortType},
+    port_library::GPPortOperations,
+    result::GP_OK,
+};
+
+use crate::{
+    check,
+    util::{c_string, ffi_guard, read_c_str, Guard},
+    GpError, Result,
+};
+
+/// A port provided by an io library.
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub struct PortInfo {
+    /// The type of the port.
+    pub port_type: GPPortType,
+    /// The name shown to users.
+    pub name: String,
+    /// The path selecting the port, e.g. `usb:001,004`.
+    pub path: String,
+}
+
+/// An io library.
+///
+/// A driver is created by [`PortDriver::open`] when a port it provides is
+/// opened and dropped when the port is closed. Operations the driver does
+/// not implement fail with [`GpError::NotSupported`].
+pub trait PortDriver: Sized + 'static {
+    /// The port types the library handles.
+    const TYPE: GPPortType;
+
+    /// The ports the library provides.
+    fn ports() -> Vec<PortInfo>;
+
+    /// Opens the port at `path`.
+    fn open(path: &str) -> Result<Self>;
+
+    /// Closes the port.
+    fn close(self) -> Result<()> {
+        Ok(())
+    }
+
+    /// Reads into `buf` waiting up to `timeout` milliseconds, returning the
+    /// number of bytes read.
+    fn read(&mut self, buf: &mut [u8], timeout: i32) -> Result<usize>;
+
+    /// Writes `data`, returning the number of bytes written.
+    fn write(&mut self, data: &[u8]) -> Result<usize>;
+
+    /// Reads from the interrupt endpoint into `buf` waiting up to `timeout`
+    /// milliseconds, returning the number of bytes read.
+    fn check_int(&mut self, _buf: &mut [u8], _timeout: i32) -> Result<usize> {
+        Err(GpError::NotSupported)
+    }
+
+    /// Applies new port settings.
+    fn update(&mut self, _settings: &GPPortSettings) -> Result<()> {
+        Ok(())
+    }
+
+    /// Resets the port.
+    fn reset(&mut self) -> Result<()> {
+        Err(GpError::NotSupported)
+    }
+}
+
+/// Exports the iolib entry points for a [`PortDriver`].
+///
+/// Generates `gp_port_library_type`, `gp_port_library_list` and
+/// `gp_port_library_operations`, so it may be used only once per library.
+/// The crate must be built as a `cdylib`.
+#[macro_export]
+macro_rules! export_iolib {
+    ($driver:ty) => {
+        #[no_mangle]
+        pub extern "C" fn gp_port_library_type() -> $crate::sys::port_info_list::GPPortType {
+            <$driver as $crate::iolib::PortDriver>::TYPE
+        }
+
+        #[no_mangle]
+        pub extern "C" fn gp_port_library_list(
+            list: *mut $crate::sys::port_info_list::GPPortInfoList,
+        ) -> ::std::os::raw::c_int {
+            unsafe { $crate::iolib::library_list::<$driver>(list) }
+        }
+
+        #[no_mangle]
+        pub extern "C" fn gp_port_library_operations(
+        ) -> *mut $crate::sys::port_library::GPPortOperations {
+            $crate::iolib::library_operations::<$driver>()
+        }
+    };
+}
+
+/// Implementation of `gp_port_library_list` for [`export_iolib!`](crate::export_iolib).
+///
+/// # Safety
+///
+/// `list` must point to a valid port info list.
+#[doc(hidden)]
+pub unsafe fn library_list<D: PortDriver>(list: *mut GPPortInfoList) -> c_int {
+    ffi_guard(|| {
+        for port in D::ports() {
+            let name = c_string(&port.name)?;
+            let path = c_string(&port.path)?;
+
+            let mut info = ptr::null_mut();
+            check(port_info_list::gp_port_info_new(&mut info))?;
+            // Owned by the list once appended
+            let mut info = Guard::new(info, |info| {
+                if !info.is_null() {
+                    free_info(info);
+                }
+            });
+            check(port_info_list::gp_port_info_set_type(
+                info.ptr,
+                port.port_type,
+            ))?;
+            check(port_info_list::gp_port_info_set_name(
+                info.ptr,
+                name.as_ptr(),
+            ))?;
+            check(port_info_list::gp_port_info_set_path(
+                info.ptr,
+                path.as_ptr(),
+            ))?;
+            check(port_info_list::gp_port_info_list_append(list, info.ptr))?;
+            info.ptr = ptr::null_mut();
+        }
+        Ok(GP_OK)
+    })
+}
+
+/// Frees a port info that is not in a list, as `gp_port_info_list_free`
+/// frees those that are.
+///
+/// # Safety
+///
+/// `info` must have been allocated by `gp_port_info_new`.
+unsafe fn free_info(info: GPPortInfo) {
+    for get in [
+        port_info_list::gp_port_info_get_name,
+        port_info_list::gp_port_info_get_path,
+        port_info_list::gp_port_info_get_library_filename,
+    ] {
+        let mut string = ptr::null_mut();
+        get(info, &mut string);
+        libc::free(string.cast());
+    }
+    libc::free(info.cast());
+}
+
+/// Implementation of `gp_port_library_operations` for [`export_iolib!`](crate::export_iolib).
+#[doc(hidden)]
+pub fn library_operations<D: PortDriver>() -> *mut GPPortOperations {
+    // Freed by libgphoto2_port with free(), all zeroes leaves every operation unset
+    let operations =
+        unsafe { libc::calloc(1, mem::size_of::<GPPortOperations>()) } as *mut GPPortOperations;
+
+    if let Some(operations) = unsafe { operations.as_mut() } {
+        operations.init = Some(init);
+        operations.exit = Some(exit::<D>);
+        operations.open = Some(open::<D>);
+        operations.close = Some(close::<D>);
+        operations.read = Some(read::<D>);
+        operations.check_int = Some(check_int::<D>);
+        operations.write = Some(write::<D>);
+        operations.update = Some(update::<D>);
+        operations.reset = Some(reset::<D>);
+    }
+
+    operations
+}
+
+/// The driver stored in the private data of `port` by `open_port`.
+///
+/// # Safety
+///
+/// `port` must have been opened by `open_port::<D>` and not closed.
+unsafe fn driver<'a, D: PortDriver>(port: *mut GPPort) -> Result<&'a mut D> {
+    let driver = (*port).pl as *mut D;
+
+    // Like the iolibs of libgphoto2, operations on a closed port are I/O errors
+    driver.as_mut().ok_or(GpError::Io)
+}
+
+/// Opens `path` with the driver `D` and stores it in the private data of `port`.
+///
+/// # Safety
+///
+/// `port` must point to a valid port that is not open.
+pub(crate) unsafe fn open_port<D: PortDriver>(port: *mut GPPort, path: &str) -> Result<()> {
+    let driver = D::open(path)?;
+    (*port).pl = Box::into_raw(Box::new(driver)) as *mut _;
+
+    Ok(())
+}
+
+/// Closes the driver stored in the private data of `port`, if any.
+///
+/// # Safety
+///
+/// `port` must point to a valid port, opened by `open_port::<D>` if the
+/// private data is set.
+unsafe fn close_port<D: PortDriver>(port: *mut GPPort) -> Result<()> {
+    let driver = (*port).pl as *mut D;
+    if driver.is_null() {
+        return Ok(());
+    }
+
+    (*port).pl = ptr::null_mut();
+    Box::from_raw(driver).close()
+}
+
+/// The `size` bytes at `bytes`, or nothing for a null pointer.
+unsafe fn buffer<'a>(bytes: *const c_char, size: c_int) -> &'a [u8] {
+    if bytes.is_null() || size <= 0 {
+        &[]
+    } else {
+        slice::from_raw_parts(bytes as *const u8, size as usize)
+    }
+}
+
+/// Like `buffer`, for a buffer to read into.
+unsafe fn buffer_mut<'a>(bytes: *mut c_char, size: c_int) -> &'a mut [u8] {
+    if bytes.is_null() || size <= 0 {
+        &mut []
+    } else {
+        slice::from_raw_parts_mut(bytes as *mut u8, size as usize)
+    }
+}
+
+extern "C" fn init(port: *mut GPPort) -> c_int {
+    ffi_guard(|| unsafe {
+        (*port).pl = ptr::null_mut();
+        Ok(GP_OK)
+    })
+}
+
+extern "C" fn exit<D: PortDriver>(port: *mut GPPort) -> c_int {
+    ffi_guard(|| unsafe { close_port::<D>(port).map(|()| GP_OK) })
+}
+
+extern "C" fn open<D: PortDriver>(port: *mut GPPort) -> c_int {
+    ffi_guard(|| unsafe {
+        let mut info = ptr::null_mut();
+        check(libgphoto2_sys::port::gp_port_get_info(port, &mut info))?;
+
+        let mut path = ptr::null_mut();
+        check(port_info_list::gp_port_info_get_path(info, &mut path))?;
+
+        let path = read_c_str(path).unwrap_or_default();
+        open_port::<D>(port, &path).map(|()| GP_OK)
+    })
+}
+
+extern "C" fn close<D: PortDriver>(port: *mut GPPort) -> c_int {
+    ffi_guard(|| unsafe { close_port::<D>(port).map(|()| GP_OK) })
+}
+
+extern "C" fn read<D: PortDriver>(port: *mut GPPort, bytes: *mut c_char, size: c_int) -> c_int {
+    ffi_guard(|| unsafe {
+        let read = driver::<D>(port)?.read(buffer_mut(bytes, size), (*port).timeout)?;
+        Ok(read.min(size as usize) as c_int)
+    })
+}
+
+extern "C" fn check_int<D: PortDriver>(
+    port: *mut GPPort,
+    bytes: *mut c_char,
+    size: c_int,
+    timeout: c_int,
+) -> c_int {
+    ffi_guard(|| unsafe {
+        let read = driver::<D>(port)?.check_int(buffer_mut(bytes, size), timeout)?;
+        Ok(read.min(size as usize) as c_int)
+    })
+}
+
+extern "C" fn write<D: PortDriver>(port: *mut GPPort, bytes: *const c_char, size: c_int) -> c_int {
+    ffi_guard(|| unsafe {
+        let written = driver::<D>(port)?.write(buffer(bytes, size))?;
+        Ok(written.min(size as usize) as c_int)
+    })
+}
+
+extern "C" fn update<D: PortDriver>(port: *mut GPPort) -> c_int {
+    ffi_guard(|| unsafe {
+        // Settings may be changed before the port is opened
+        if let Some(driver) = ((*port).pl as *mut D).as_mut() {
+            driver.update(&(*port).settings_pending)?;
+        }
+
+        ptr::copy_nonoverlapping(&(*port).settings_pending, &mut (*port).settings, 1);
+        Ok(GP_OK)
+    })
+}
+
+extern "C" fn reset<D: PortDriver>(port: *mut GPPort) -> c_int {
+    ffi_guard(|| unsafe { driver::<D>(port)?.reset().map(|()| GP_OK) })
+}
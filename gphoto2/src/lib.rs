@@ -0,0 +1,927 @@
+//! Safe wrappers around the libgphoto2 bindings of [`libgphoto2_sys`].
+
+pub mod abilities;
+pub mod camera;
+pub mod camlib;
+pub mod cancel;
+pub mod context;
+pub mod error;
+pub mod iolib;
+pub mod list;
+pub mod matrix;
+pub mod port;
+pub mod progress;
+pub mod udev;
+mod util;
+pub mod widget;
+
+pub use error::{check, GpError, Result};
+pub use libgphoto2_sys as sys;
+
+#[cfg(test)]
+mod tests {
+    use crate::{check, GpError};
+
+    /// Loads libgphoto2 when it is not linked.
+    fn setup() {
+        #[cfg(feature = "runtime-load")]
+        libgphoto2_sys::load().unwrap();
+    }
+
+    /// Calls a variadic function of `sys::context`, which is only reachable
+    /// through the function table with `runtime-load`.
+    macro_rules! context_variadic {
+        ($name:ident($($arg:expr),* $(,)?)) => {{
+            #[cfg(not(feature = "runtime-load"))]
+            let ret = crate::sys::context::$name($($arg),*);
+            #[cfg(feature = "runtime-load")]
+            let ret = (crate::sys::context::functions().$name)($($arg),*);
+            ret
+        }};
+    }
+
+    #[test]
+    fn test_camera() {
+        use crate::{camera::Camera, context::Context};
+
+        setup();
+
+        let camera = Camera::alloc().unwrap();
+        let clone = camera.clone();
+        drop(camera);
+        drop(clone);
+
+        // No camera is attached to the test machine
+        let mut errors = Vec::new();
+        let ctx = Context::new()
+            .unwrap()
+            .on_error(|text| errors.push(text.to_string()));
+        assert_eq!(Camera::new(&ctx).unwrap_err(), GpError::ModelNotFound);
+
+        assert_eq!(
+            Camera::open(&ctx, "No Such Camera", "usb:").unwrap_err(),
+            GpError::ModelNotFound
+        );
+        assert_eq!(
+            Camera::open(&ctx, "USB PTP Class Camera", "nosuchport:").unwrap_err(),
+            GpError::UnknownPort
+        );
+        drop(ctx);
+        assert!(!errors.is_empty());
+    }
+
+    #[test]
+    fn test_abilities_list() {
+        use crate::{
+            abilities::{Abilities, AbilitiesFilter, AbilitiesList},
+            context::Context,
+            sys::{
+                abilities_list::{CameraDriverStatus, CameraOperation},
+                port_info_list::GPPortType,
+            },
+        };
+
+        setup();
+
+        let mut list = AbilitiesList::new().unwrap();
+        let mut trigger = Abilities::new("Trigger");
+        trigger.port = GPPortType::GP_PORT_USB | GPPortType::GP_PORT_PTPIP;
+        trigger.operations =
+            CameraOperation::GP_OPERATION_CONFIG | CameraOperation::GP_OPERATION_TRIGGER_CAPTURE;
+        trigger.usb_vendor = 0x04a9;
+        trigger.usb_product = 0x3294;
+        let mut serial = Abilities::new("Serial");
+        serial.port = GPPortType::GP_PORT_SERIAL;
+        serial.status = CameraDriverStatus::GP_DRIVER_STATUS_DEPRECATED;
+        list.append(&trigger).unwrap();
+        list.append(&serial).unwrap();
+
+        assert_eq!(list.len(), 2);
+        assert_eq!(
+            list.iter().collect::<Vec<_>>(),
+            [trigger.clone(), serial.clone()]
+        );
+        assert_eq!(list.lookup_model("TRIGGER"), Ok(trigger.clone()));
+        assert_eq!(list.lookup_model("Nope"), Err(GpError::ModelNotFound));
+
+        let models = |filter: AbilitiesFilter| {
+            list.filter(&filter)
+                .map(|abilities| abilities.model)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(models(AbilitiesFilter::new()), ["Trigger", "Serial"]);
+        assert_eq!(
+            models(
+                AbilitiesFilter::new()
+                    .port(GPPortType::GP_PORT_USB)
+                    .operations(CameraOperation::GP_OPERATION_TRIGGER_CAPTURE)
+            ),
+            ["Trigger"]
+        );
+        assert_eq!(
+            models(AbilitiesFilter::new().operations(CameraOperation::GP_OPERATION_CAPTURE_IMAGE)),
+            [""; 0]
+        );
+        assert_eq!(
+            models(AbilitiesFilter::new().usb_id(0x04a9, 0x3294)),
+            ["Trigger"]
+        );
+        assert_eq!(models(AbilitiesFilter::new().usb_vendor(0x04b0)), [""; 0]);
+        assert_eq!(
+            models(AbilitiesFilter::new().status(CameraDriverStatus::GP_DRIVER_STATUS_DEPRECATED)),
+            ["Serial"]
+        );
+
+        let ctx = Context::new().unwrap();
+        let list = AbilitiesList::load(&ctx).unwrap();
+        assert!(list.lookup_model("USB PTP Class Camera").is_ok());
+    }
+
+    #[test]
+    fn test_autodetect() {
+        use crate::{
+            camera::{self, DetectedCamera, UsbAddress},
+            context::Context,
+            sys::port_info_list::GPPortType,
+        };
+
+        let usb = DetectedCamera::new("Nikon DSC D750", "usb:002,013");
+        assert_eq!(usb.port_type, GPPortType::GP_PORT_USB);
+        assert_eq!(usb.usb, Some(UsbAddress { bus: 2, device: 13 }));
+
+        let ptpip = DetectedCamera::new("PTP/IP Camera", "ptpip:192.168.1.1");
+        assert_eq!(ptpip.port_type, GPPortType::GP_PORT_PTPIP);
+        assert_eq!(ptpip.usb, None);
+
+        assert_eq!(DetectedCamera::new("", "usb:").usb, None);
+        assert_eq!(
+            DetectedCamera::new("Mass Storage", "disk:/media/card").port_type,
+            GPPortType::GP_PORT_DISK
+        );
+        assert_eq!(
+            DetectedCamera::new("", "").port_type,
+            GPPortType::GP_PORT_NONE
+        );
+
+        setup();
+
+        // No camera is attached to the test machine
+        let ctx = Context::new().unwrap();
+        assert_eq!(camera::autodetect(&ctx), Ok(Vec::new()));
+    }
+
+    #[test]
+    fn test_check() {
+        assert_eq!(check(0), Ok(0));
+        assert_eq!(check(3), Ok(3));
+        assert_eq!(check(-53), Err(GpError::IoUsbClaim));
+        assert_eq!(check(-112), Err(GpError::Cancel));
+        assert_eq!(check(-999), Err(GpError::Unknown(-999)));
+
+        assert_eq!(GpError::ModelNotFound.code(), -105);
+        assert_eq!(GpError::from_code(-999).code(), -999);
+    }
+
+    mod camlib {
+        use std::{ffi::CStr, mem, ptr, ptr::NonNull};
+
+        use crate::{
+            abilities::Abilities,
+            camlib::CameraDriver,
+            port::Port,
+            sys::{
+                camera::{Camera, CameraFunctions, CameraText},
+                widget::CameraWidgetType,
+            },
+            widget::{Widget, WidgetKind},
+            GpError, Result,
+        };
+
+        struct TestDriver {
+            summaries: usize,
+            owner: String,
+        }
+
+        impl CameraDriver for TestDriver {
+            const ID: &'static str = "test";
+            const GET_CONFIG: bool = true;
+            const SET_CONFIG: bool = true;
+            const SUMMARY: bool = true;
+            const MANUAL: bool = true;
+
+            fn abilities() -> Vec<Abilities> {
+                vec![Abilities::new("Test Camera")]
+            }
+
+            fn init(_port: Port) -> Result<Self> {
+                Ok(TestDriver {
+                    summaries: 0,
+                    owner: "nobody".to_string(),
+                })
+            }
+
+            fn get_config(&mut self) -> Result<Widget> {
+                let window = Widget::new(CameraWidgetType::GP_WIDGET_WINDOW, "Test")?;
+                let owner = Widget::new(CameraWidgetType::GP_WIDGET_TEXT, "Owner")?;
+                owner.set_name("owner")?;
+                owner.set_text(&self.owner)?;
+                window.append(owner)?;
+
+                Ok(window)
+            }
+
+            fn set_config(&mut self, config: &Widget) -> Result<()> {
+                // The tree stays with libgphoto2
+                let window = Widget::new(CameraWidgetType::GP_WIDGET_WINDOW, "Stolen")?;
+                assert_eq!(
+                    window.append(config.root()).unwrap_err(),
+                    GpError::BadParameters
+                );
+
+                if let WidgetKind::Text(owner) = config.child_by_name("owner")?.kind()? {
+                    self.owner = owner;
+                }
+                Ok(())
+            }
+
+            fn summary(&mut self) -> Result<String> {
+                self.summaries += 1;
+                Ok(format!("summary {}", self.summaries))
+            }
+
+            fn manual(&mut self) -> Result<String> {
+                panic!("the manual is missing");
+            }
+        }
+
+        crate::export_camlib!(TestDriver);
+
+        fn text(text: &CameraText) -> &str {
+            unsafe { CStr::from_ptr(text.text.as_ptr()) }
+                .to_str()
+                .unwrap()
+        }
+
+        #[test]
+        fn test_camlib() {
+            super::setup();
+
+            let mut text_buf: Box<CameraText> = Box::new(unsafe { mem::zeroed() });
+            assert_eq!(camera_id(&mut *text_buf), 0);
+            assert_eq!(text(&text_buf), "test");
+
+            let mut functions: CameraFunctions = unsafe { mem::zeroed() };
+            let mut camera = Camera {
+                port: ptr::null_mut(),
+                fs: ptr::null_mut(),
+                functions: &mut functions,
+                pl: ptr::null_mut(),
+                pc: ptr::null_mut(),
+            };
+            assert_eq!(camera_init(&mut camera, ptr::null_mut()), 0);
+            assert!(!camera.pl.is_null());
+
+            let summary = functions.summary.unwrap();
+            for expected in ["summary 1", "summary 2"] {
+                assert_eq!(summary(&mut camera, &mut *text_buf, ptr::null_mut()), 0);
+                assert_eq!(text(&text_buf), expected);
+            }
+
+            // Panics must not unwind into libgphoto2
+            let manual = functions.manual.unwrap();
+            assert_eq!(
+                manual(&mut camera, &mut *text_buf, ptr::null_mut()),
+                GpError::Library.code()
+            );
+            // Left to libgphoto2, which reports it as unsupported
+            assert!(functions.about.is_none());
+            assert!(functions.capture.is_none());
+
+            let mut window = ptr::null_mut();
+            let get_config = functions.get_config.unwrap();
+            assert_eq!(get_config(&mut camera, &mut window, ptr::null_mut()), 0);
+            // libgphoto2 owns the tree returned by the driver
+            let window = unsafe { Widget::from_root(NonNull::new(window).unwrap()) };
+            let owner = window.child_by_name("owner").unwrap();
+            assert_eq!(
+                owner.kind().unwrap(),
+                WidgetKind::Text("nobody".to_string())
+            );
+
+            owner.set_text("Ada").unwrap();
+            let set_config = functions.set_config.unwrap();
+            assert_eq!(set_config(&mut camera, owner.as_ptr(), ptr::null_mut()), 0);
+            assert_eq!(unsafe { &*(camera.pl as *const TestDriver) }.owner, "Ada");
+            // Frees the tree, the handle given to the driver has released its reference
+            drop((window, owner));
+
+            let exit = functions.exit.unwrap();
+            assert_eq!(exit(&mut camera, ptr::null_mut()), 0);
+            assert!(camera.pl.is_null());
+        }
+    }
+
+    mod iolib {
+        use std::{collections::VecDeque, mem};
+
+        use crate::{
+            iolib::{self, PortDriver, PortInfo},
+            sys::{port::GPPort, port_info_list::GPPortType},
+            Result,
+        };
+
+        /// Echoes everything written to it.
+        struct Loopback {
+            buffer: VecDeque<u8>,
+        }
+
+        impl PortDriver for Loopback {
+            const TYPE: GPPortType = GPPortType::GP_PORT_USB;
+
+            fn ports() -> Vec<PortInfo> {
+                vec![PortInfo {
+                    port_type: GPPortType::GP_PORT_USB,
+                    name: "Loopback".to_string(),
+                    path: "usb:loopback".to_string(),
+                }]
+            }
+
+            fn open(path: &str) -> Result<Self> {
+                assert_eq!(path, "usb:loopback");
+                Ok(Loopback {
+                    buffer: VecDeque::new(),
+                })
+            }
+
+            fn read(&mut self, buf: &mut [u8], _timeout: i32) -> Result<usize> {
+                let len = buf.len().min(self.buffer.len());
+                for (dst, src) in buf.iter_mut().zip(self.buffer.drain(..len)) {
+                    *dst = src;
+                }
+                Ok(len)
+            }
+
+            fn write(&mut self, data: &[u8]) -> Result<usize> {
+                self.buffer.extend(data);
+                Ok(data.len())
+            }
+        }
+
+        crate::export_iolib!(Loopback);
+
+        #[test]
+        fn test_iolib() {
+            assert_eq!(gp_port_library_type(), GPPortType::GP_PORT_USB);
+
+            let operations = gp_port_library_operations();
+            let ops = unsafe { &*operations };
+            assert!(ops.seek.is_none());
+
+            let mut port: GPPort = unsafe { mem::zeroed() };
+            assert_eq!((ops.init.unwrap())(&mut port), 0);
+
+            // Reading a closed port fails
+            let mut buf = [0; 8];
+            assert!((ops.read.unwrap())(&mut port, buf.as_mut_ptr(), 8) < 0);
+
+            unsafe { iolib::open_port::<Loopback>(&mut port, "usb:loopback") }.unwrap();
+
+            let data = b"hello".map(|b| b as libc::c_char);
+            assert_eq!((ops.write.unwrap())(&mut port, data.as_ptr(), 5), 5);
+            assert_eq!((ops.read.unwrap())(&mut port, buf.as_mut_ptr(), 3), 3);
+            assert_eq!(buf[..3], data[..3]);
+            assert_eq!((ops.read.unwrap())(&mut port, buf.as_mut_ptr(), 8), 2);
+
+            unsafe { (*port.settings_pending.usb).inep = 0x81 };
+            assert_eq!((ops.update.unwrap())(&mut port), 0);
+            assert_eq!(unsafe { port.settings.usb.inep }, 0x81);
+
+            assert!((ops.reset.unwrap())(&mut port) < 0);
+            assert_eq!((ops.close.unwrap())(&mut port), 0);
+            assert!(port.pl.is_null());
+            assert_eq!((ops.exit.unwrap())(&mut port), 0);
+
+            unsafe { libc::free(operations as *mut libc::c_void) };
+        }
+    }
+
+    #[test]
+    fn test_context() {
+        use std::cell::Cell;
+
+        use crate::{
+            context::Context,
+            sys::context::{self, GPContextFeedback},
+        };
+
+        setup();
+
+        let idles = Cell::new(0);
+        let mut errors = Vec::new();
+        let mut updates = Vec::new();
+        {
+            let ctx = Context::new()
+                .unwrap()
+                .on_idle(|| idles.set(idles.get() + 1))
+                .on_error(|text| errors.push(text.to_string()))
+                .on_cancel(|| panic!("cancelled"))
+                .on_progress(|_, _| 7, |id, current| updates.push((id, current)), |_| ());
+
+            // Clones share the callbacks
+            let clone = ctx.clone();
+            drop(ctx);
+
+            unsafe {
+                context::gp_context_idle(clone.as_ptr());
+                context::gp_context_idle(clone.as_ptr());
+                context_variadic!(gp_context_error(
+                    clone.as_ptr(),
+                    c"%s".as_ptr(),
+                    c"failed".as_ptr()
+                ));
+                context::gp_context_progress_update(clone.as_ptr(), 7, 0.5);
+
+                // Panics must not unwind into libgphoto2
+                assert_eq!(
+                    context::gp_context_cancel(clone.as_ptr()),
+                    GPContextFeedback::GP_CONTEXT_FEEDBACK_OK
+                );
+            }
+        }
+
+        assert_eq!(idles.get(), 2);
+        assert_eq!(errors, ["failed"]);
+        assert_eq!(updates, [(7, 0.5)]);
+    }
+
+    #[test]
+    fn test_cancellation() {
+        use std::thread;
+
+        use crate::{
+            cancel::{CancellationToken, Outcome},
+            context::Context,
+            sys::context::{self, GPContextFeedback},
+        };
+
+        setup();
+
+        let token = CancellationToken::new();
+        let ctx = Context::new().unwrap().with_cancellation(token.clone());
+
+        let cancel = || unsafe { context::gp_context_cancel(ctx.as_ptr()) };
+        assert_eq!(cancel(), GPContextFeedback::GP_CONTEXT_FEEDBACK_OK);
+
+        let remote = token.clone();
+        thread::spawn(move || remote.cancel()).join().unwrap();
+        assert!(token.is_cancelled());
+        assert_eq!(cancel(), GPContextFeedback::GP_CONTEXT_FEEDBACK_CANCEL);
+
+        assert_eq!(Outcome::from_result(Ok(3)), Ok(Outcome::Completed(3)));
+        assert_eq!(
+            Outcome::<()>::from_result(Err(GpError::Cancel)),
+            Ok(Outcome::Cancelled)
+        );
+        assert_eq!(
+            Outcome::<()>::from_result(Err(GpError::Io)),
+            Err(GpError::Io)
+        );
+    }
+
+    #[test]
+    fn test_list() {
+        use crate::{list::CameraList, sys};
+
+        setup();
+
+        let mut list: CameraList = [("b", "usb:002,001"), ("a", "usb:001,001")]
+            .into_iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect();
+        list.append("c", None).unwrap();
+
+        assert_eq!(list.len(), 3);
+        assert_eq!(list.get(0), Some(("b".into(), Some("usb:002,001".into()))));
+        assert_eq!(list.get(3), None);
+        assert_eq!(list.find_by_name("c"), Some(2));
+        assert_eq!(list.find_by_name("d"), None);
+
+        list.sort().unwrap();
+        let names: Vec<_> = list.iter().map(|(name, _)| name).collect();
+        assert_eq!(names, ["a", "b", "c"]);
+        assert_eq!(
+            format!("{list:?}"),
+            r#"{"a": Some("usb:001,001"), "b": Some("usb:002,001"), "c": None}"#
+        );
+
+        let mut entries = list.into_iter();
+        assert_eq!(entries.len(), 3);
+        assert_eq!(
+            entries.next(),
+            Some(("a".to_string(), Some("usb:001,001".to_string())))
+        );
+
+        let mut list = CameraList::new().unwrap();
+        list.append("a", Some("b")).unwrap();
+        list.reset().unwrap();
+        assert!(list.is_empty());
+        assert_eq!((&list).into_iter().next(), None);
+
+        // Invalid UTF-8 is replaced rather than dropped
+        let name = c"\xffd";
+        assert_eq!(
+            unsafe { sys::list::gp_list_append(list.as_ptr(), name.as_ptr(), std::ptr::null()) },
+            0
+        );
+        assert_eq!(list.get(0), Some(("\u{fffd}d".into(), None)));
+    }
+
+    #[test]
+    fn test_matrix() {
+        use crate::{
+            abilities::Abilities,
+            matrix::{Format, SupportMatrix},
+            sys::{
+                abilities_list::{CameraFileOperation, CameraOperation},
+                port_info_list::GPPortType,
+            },
+        };
+
+        let mut ptp = Abilities::new("USB PTP Class Camera");
+        ptp.port = GPPortType::GP_PORT_USB;
+        ptp.operations =
+            CameraOperation::GP_OPERATION_CONFIG | CameraOperation::GP_OPERATION_CAPTURE_IMAGE;
+        ptp.file_operations = CameraFileOperation::GP_FILE_OPERATION_DELETE;
+        ptp.usb_class = 6;
+        ptp.usb_protocol = -1;
+        ptp.library = "/usr/lib/libgphoto2/2.5.30/ptp2.so".to_string();
+        let mut canon = Abilities::new("Canon \"Quoted\", | Model");
+        canon.port = GPPortType::GP_PORT_USB | GPPortType::GP_PORT_PTPIP;
+        canon.usb_vendor = 0x04a9;
+        canon.usb_product = 0x3294;
+
+        let matrix: SupportMatrix = [ptp, canon].into_iter().collect();
+        assert_eq!(matrix.models()[0].usb_vendor, 0x04a9);
+
+        let output = |format| {
+            let mut output = Vec::new();
+            matrix.write(format, &mut output).unwrap();
+            String::from_utf8(output).unwrap()
+        };
+
+        assert_eq!(
+            output(Format::Csv),
+            "model,status,device_type,ports,usb_vendor,usb_product,usb_class,usb_subclass,\
+             usb_protocol,library,operations,file_operations,folder_operations\n\
+             \"Canon \"\"Quoted\"\", | Model\",production,still_camera,usb;ptpip,04a9,3294,,,,,,,\n\
+             USB PTP Class Camera,production,still_camera,usb,,,6,0,*,ptp2,\
+             capture_image;config,delete,\n"
+        );
+
+        let markdown = output(Format::Markdown);
+        let lines: Vec<_> = markdown.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert!(lines[1].starts_with("| --- | --- |"));
+        assert_eq!(
+            lines[2],
+            "| Canon \"Quoted\", \\| Model | production | still_camera | usb, ptpip | 04a9 \
+             | 3294 |  |  |  |  |  |  |  |"
+        );
+
+        let json = output(Format::Json);
+        assert!(json.starts_with("[\n  {\n    \"model\": \"Canon \\\"Quoted\\\", | Model\",\n"));
+        assert!(json.contains("    \"usb_vendor\": \"04a9\",\n"));
+        assert!(json.contains("    \"usb_class\": 6,\n"));
+        assert!(json.contains("    \"usb_subclass\": 0,\n"));
+        assert!(json.contains("    \"usb_protocol\": \"*\",\n"));
+        assert!(json.contains("    \"usb_protocol\": null,\n"));
+        assert!(json.contains("    \"operations\": [\"capture_image\", \"config\"],\n"));
+        assert!(json.contains("    \"folder_operations\": []\n  },\n"));
+        assert!(json.ends_with("  }\n]\n"));
+
+        let empty = SupportMatrix::default();
+        let mut output = Vec::new();
+        empty.write(Format::Json, &mut output).unwrap();
+        assert_eq!(output, b"[]\n");
+    }
+
+    #[test]
+    fn test_widget() {
+        use std::{
+            ptr::NonNull,
+            time::{Duration, UNIX_EPOCH},
+        };
+
+        use crate::{
+            sys::widget::CameraWidgetType,
+            widget::{Widget, WidgetKind},
+        };
+
+        setup();
+
+        let window = Widget::new(CameraWidgetType::GP_WIDGET_WINDOW, "Camera settings").unwrap();
+        let section = Widget::new(CameraWidgetType::GP_WIDGET_SECTION, "Capture").unwrap();
+        let owner = Widget::new(CameraWidgetType::GP_WIDGET_TEXT, "Owner").unwrap();
+        owner.set_name("owner").unwrap();
+        owner.set_text("Ada").unwrap();
+        let zoom = Widget::new(CameraWidgetType::GP_WIDGET_RANGE, "Zoom").unwrap();
+        zoom.set_range(1.0, 4.0, 0.5).unwrap();
+        zoom.set_range_value(2.5).unwrap();
+        let iso = Widget::new(CameraWidgetType::GP_WIDGET_RADIO, "ISO Speed").unwrap();
+        iso.set_name("iso").unwrap();
+        iso.add_choice("100").unwrap();
+        iso.add_choice("200").unwrap();
+        iso.set_choice("100").unwrap();
+        let date = Widget::new(CameraWidgetType::GP_WIDGET_DATE, "Date").unwrap();
+        date.set_readonly(true).unwrap();
+
+        assert_eq!(
+            owner.set_toggle(true).unwrap_err(),
+            crate::GpError::BadParameters
+        );
+        assert_eq!(
+            section.set_text("x").unwrap_err(),
+            crate::GpError::BadParameters
+        );
+        assert_eq!(
+            date.set_date(UNIX_EPOCH + Duration::from_secs(1 << 40))
+                .unwrap_err(),
+            crate::GpError::BadParameters
+        );
+        date.set_date(UNIX_EPOCH - Duration::from_secs(86400))
+            .unwrap();
+
+        for child in [owner, zoom, iso, date] {
+            section.append(child).unwrap();
+        }
+        // A widget still referenced elsewhere cannot change trees
+        let kept = section.clone();
+        assert_eq!(
+            window.append(kept).unwrap_err(),
+            crate::GpError::BadParameters
+        );
+        window.append(section).unwrap();
+        // Nor can a tree lent by libgphoto2, even through its only handle
+        let lent = Widget::new(CameraWidgetType::GP_WIDGET_SECTION, "Lent").unwrap();
+        let borrowed = unsafe { Widget::from_borrowed(NonNull::new(lent.as_ptr()).unwrap()) };
+        drop(lent);
+        assert_eq!(
+            window.append(borrowed).unwrap_err(),
+            crate::GpError::BadParameters
+        );
+
+        let sections: Vec<_> = window.children().collect();
+        assert_eq!(sections.len(), 1);
+        assert_eq!(sections[0].label(), "Capture");
+        assert_eq!(sections[0].kind().unwrap(), WidgetKind::Section);
+        assert_eq!(sections[0].children().len(), 4);
+
+        let iso = window.child_by_name("iso").unwrap();
+        assert_eq!(
+            iso.kind().unwrap(),
+            WidgetKind::Radio {
+                value: "100".to_string(),
+                choices: vec!["100".to_string(), "200".to_string()]
+            }
+        );
+        iso.set_choice("200").unwrap();
+        assert_eq!(
+            window.child_by_label("ISO Speed").unwrap().kind().unwrap(),
+            WidgetKind::Radio {
+                value: "200".to_string(),
+                choices: vec!["100".to_string(), "200".to_string()]
+            }
+        );
+        assert_eq!(iso.parent().unwrap().label(), "Capture");
+        assert_eq!(iso.root().as_ptr(), window.as_ptr());
+        assert!(window.parent().is_none());
+        assert!(window.child_by_name("missing").is_err());
+
+        let kinds: Vec<_> = iso
+            .parent()
+            .unwrap()
+            .children()
+            .map(|child| child.kind().unwrap())
+            .collect();
+        assert_eq!(
+            kinds,
+            [
+                WidgetKind::Text("Ada".to_string()),
+                WidgetKind::Range {
+                    value: 2.5,
+                    min: 1.0,
+                    max: 4.0,
+                    step: 0.5
+                },
+                WidgetKind::Radio {
+                    value: "200".to_string(),
+                    choices: vec!["100".to_string(), "200".to_string()]
+                },
+                WidgetKind::Date(UNIX_EPOCH - Duration::from_secs(86400)),
+            ]
+        );
+        assert!(window.child_by_label("Date").unwrap().is_readonly());
+
+        // Handles into the tree keep it alive after the window is dropped
+        drop(window);
+        assert_eq!(iso.root().label(), "Camera settings");
+    }
+
+    #[test]
+    fn test_udev() {
+        use crate::{
+            abilities::Abilities, sys::abilities_list::GphotoDeviceType, udev::UdevGenerator,
+        };
+
+        let mut ptp = Abilities::new("USB PTP Class Camera");
+        ptp.usb_class = 6;
+        ptp.usb_protocol = -1;
+        ptp.usb_subclass = 1;
+        ptp.usb_protocol = 1;
+        ptp.id = "PTP".to_string();
+        let mut mtp = Abilities::new("MTP Device");
+        mtp.usb_class = 666;
+        let mut image = Abilities::new("Still Image Device");
+        image.usb_class = 6;
+        image.usb_subclass = -1;
+        image.usb_protocol = -1;
+        let mut canon = Abilities::new("Canon EOS 5D Mark IV");
+        canon.usb_vendor = 0x04a9;
+        canon.usb_product = 0x3294;
+        canon.id = "PTP".to_string();
+        let mut player = Abilities::new("Music Player");
+        player.usb_vendor = 0x1234;
+        player.usb_product = 0xabcd;
+        player.device_type = GphotoDeviceType::GP_DEVICE_AUDIO_PLAYER;
+        let mut duplicate = canon.clone();
+        duplicate.model = "Canon EOS 5D Mark IV (PTP mode)".to_string();
+
+        let generator: UdevGenerator = [ptp.clone(), mtp, image, canon, player, duplicate, ptp]
+            .into_iter()
+            .collect();
+        for mode in ["", "0668", "0660\", RUN+=\"/bin/sh"] {
+            assert_eq!(
+                generator.clone().mode(mode).unwrap_err(),
+                crate::GpError::BadParameters
+            );
+        }
+        for name in ["", "cam era", "camera\"", "plugdev\n"] {
+            assert!(generator.clone().group(name).is_err());
+            assert!(generator.clone().tag(name).is_err());
+        }
+        let generator = generator
+            .mode("0660")
+            .and_then(|generator| generator.group("camera"))
+            .and_then(|generator| generator.tag("uaccess"))
+            .unwrap();
+
+        let mut rules = Vec::new();
+        generator.write_rules(&mut rules).unwrap();
+        assert_eq!(
+            String::from_utf8(rules).unwrap(),
+            "# udev rules for the cameras supported by libgphoto2\n\
+             ACTION==\"remove\", GOTO=\"gphoto2_rules_end\"\n\
+             SUBSYSTEM!=\"usb\", GOTO=\"gphoto2_rules_end\"\n\
+             ENV{DEVTYPE}!=\"usb_device\", GOTO=\"gphoto2_rules_end\"\n\
+             \n\
+             # USB PTP Class Camera\n\
+             ENV{ID_USB_INTERFACES}==\"*:060101:*\", ENV{ID_GPHOTO2}=\"1\", \
+             ENV{GPHOTO2_DRIVER}=\"PTP\", MODE=\"0660\", GROUP=\"camera\", TAG+=\"uaccess\"\n\
+             \n\
+             # Still Image Device\n\
+             ENV{ID_USB_INTERFACES}==\"*:06????:*\", ENV{ID_GPHOTO2}=\"1\", \
+             ENV{GPHOTO2_DRIVER}=\"proprietary\", MODE=\"0660\", GROUP=\"camera\", \
+             TAG+=\"uaccess\"\n\
+             \n\
+             # Canon EOS 5D Mark IV\n\
+             ATTR{idVendor}==\"04a9\", ATTR{idProduct}==\"3294\", ENV{ID_GPHOTO2}=\"1\", \
+             ENV{GPHOTO2_DRIVER}=\"PTP\", MODE=\"0660\", GROUP=\"camera\", TAG+=\"uaccess\"\n\
+             \n\
+             # Music Player\n\
+             ATTR{idVendor}==\"1234\", ATTR{idProduct}==\"abcd\", ENV{ID_GPHOTO2}=\"1\", \
+             ENV{GPHOTO2_DRIVER}=\"proprietary\", ENV{ID_MEDIA_PLAYER}=\"1\", \
+             MODE=\"0660\", GROUP=\"camera\", TAG+=\"uaccess\"\n\
+             \n\
+             LABEL=\"gphoto2_rules_end\"\n"
+        );
+
+        let mut hwdb = Vec::new();
+        generator.write_hwdb(&mut hwdb).unwrap();
+        let hwdb = String::from_utf8(hwdb).unwrap();
+        // Interface classes are not part of the modalias of USB devices
+        assert!(!hwdb.contains("Class Camera"));
+        assert!(hwdb.contains(
+            "usb:v1234pABCD*\n GPHOTO2_DRIVER=proprietary\n ID_GPHOTO2=1\n ID_MEDIA_PLAYER=1\n"
+        ));
+        assert_eq!(hwdb.matches("usb:v04A9p3294*").count(), 1);
+
+        let mut rules = Vec::new();
+        generator.write_hwdb_rules(&mut rules).unwrap();
+        let rules = String::from_utf8(rules).unwrap();
+        assert!(rules.contains(
+            "# USB PTP Class Camera\n\
+             ENV{ID_USB_INTERFACES}==\"*:060101:*\", ENV{ID_GPHOTO2}=\"1\", \
+             ENV{GPHOTO2_DRIVER}=\"PTP\", MODE=\"0660\", GROUP=\"camera\", TAG+=\"uaccess\"\n"
+        ));
+        assert!(rules.contains("ENV{ID_USB_INTERFACES}==\"*:06????:*\""));
+        assert!(rules.contains(
+            "IMPORT{builtin}=\"hwdb --subsystem=usb\"\n\
+             ENV{ID_GPHOTO2}==\"1\", MODE=\"0660\", GROUP=\"camera\", TAG+=\"uaccess\"\n"
+        ));
+    }
+
+    #[test]
+    fn test_progress() {
+        use std::time::{Duration, Instant};
+
+        use crate::{
+            context::Context,
+            progress::{ProgressEvent, ProgressTracker},
+            sys::context,
+        };
+
+        let mut tracker = ProgressTracker::new();
+        let start = Instant::now();
+
+        let (event, task) = tracker.start_at(start, 200.0, "Downloading");
+        let outer = task.id;
+        assert_eq!(
+            event,
+            ProgressEvent::Started {
+                id: outer,
+                target: 200.0,
+                text: "Downloading".to_string()
+            }
+        );
+        assert_eq!(task.eta(), None);
+
+        let (_, task) = tracker.start_at(start, 10.0, "IMG_0001.JPG");
+        let inner = task.id;
+        assert_eq!(task.parent, Some(outer));
+
+        let (event, task) = tracker
+            .update_at(start + Duration::from_secs(2), outer, 50.0)
+            .unwrap();
+        assert_eq!(
+            event,
+            ProgressEvent::Updated {
+                id: outer,
+                current: 50.0
+            }
+        );
+        assert_eq!(task.fraction(), 0.25);
+        assert_eq!(task.throughput(), 25.0);
+        assert_eq!(task.eta(), Some(Duration::from_secs(6)));
+
+        assert_eq!(
+            tracker.stop(inner).unwrap().0,
+            ProgressEvent::Stopped { id: inner }
+        );
+        assert!(tracker.stop(inner).is_none());
+        assert!(tracker.update(inner, 1.0).is_none());
+        assert_eq!(tracker.tasks().count(), 1);
+
+        setup();
+
+        let mut events = Vec::new();
+        let ctx = Context::new()
+            .unwrap()
+            .on_progress_events(|event, _| events.push(event.clone()));
+        unsafe {
+            let id = context_variadic!(gp_context_progress_start(
+                ctx.as_ptr(),
+                100.0,
+                c"%s".as_ptr(),
+                c"Uploading".as_ptr(),
+            ));
+            context::gp_context_progress_update(ctx.as_ptr(), id, 100.0);
+            context::gp_context_progress_stop(ctx.as_ptr(), id);
+        }
+        drop(ctx);
+
+        assert_eq!(
+            events,
+            [
+                ProgressEvent::Started {
+                    id: 1,
+                    target: 100.0,
+                    text: "Uploading".to_string()
+                },
+                ProgressEvent::Updated {
+                    id: 1,
+                    current: 100.0
+                },
+                ProgressEvent::Stopped { id: 1 },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_error_display() {
+        setup();
+
+        let text = GpError::IoUsbClaim.to_string();
+        assert!(!text.is_empty());
+        assert!(GpError::Unknown(-999).to_string().ends_with("(-999)"));
+    }
+}
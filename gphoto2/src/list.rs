@@ -0,0 +1,215 @@
+//! Lists of name and value pairs.
+
+use std::{borrow::Cow, ffi::CStr, fmt, iter::FusedIterator, ptr, ptr::NonNull};
+
+use libc::{c_char, c_int};
+use libgphoto2_sys::list;
+
+use crate::{
+    check,
+    util::{c_string, Indices},
+    GpError, Result,
+};
+
+/// An owned `CameraList`, e.g. the models and ports of detected cameras.
+///
+/// Names and values are borrowed from the list, so they cannot outlive it
+/// or be kept across changes to it. Invalid UTF-8 in them is replaced with
+/// `U+FFFD`, in which case they are copied.
+pub struct CameraList {
+    ptr: NonNull<list::CameraList>,
+}
+
+/// The string `ptr` points to, with invalid UTF-8 replaced.
+///
+/// # Safety
+///
+/// `ptr` must be null or point to a NUL terminated string that lives for `'a`.
+unsafe fn str_from_ptr<'a>(ptr: *const c_char) -> Option<Cow<'a, str>> {
+    if ptr.is_null() {
+        None
+    } else {
+        Some(CStr::from_ptr(ptr).to_string_lossy())
+    }
+}
+
+impl CameraList {
+    /// Creates an empty list.
+    pub fn new() -> Result<Self> {
+        let mut ptr = ptr::null_mut();
+        check(unsafe { list::gp_list_new(&mut ptr) })?;
+
+        Ok(CameraList {
+            ptr: NonNull::new(ptr).ok_or(GpError::NoMemory)?,
+        })
+    }
+
+    /// The wrapped `CameraList`.
+    pub fn as_ptr(&self) -> *mut list::CameraList {
+        self.ptr.as_ptr()
+    }
+
+    /// The number of entries.
+    pub fn len(&self) -> usize {
+        check(unsafe { list::gp_list_count(self.as_ptr()) }).map_or(0, |count| count as usize)
+    }
+
+    /// Returns whether the list has no entries.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The name and value of the entry at `index`.
+    pub fn get(&self, index: usize) -> Option<(Cow<'_, str>, Option<Cow<'_, str>>)> {
+        let index = c_int::try_from(index).ok()?;
+        let (mut name, mut value) = (ptr::null(), ptr::null());
+
+        unsafe {
+            check(list::gp_list_get_name(self.as_ptr(), index, &mut name)).ok()?;
+            check(list::gp_list_get_value(self.as_ptr(), index, &mut value)).ok()?;
+
+            Some((str_from_ptr(name).unwrap_or_default(), str_from_ptr(value)))
+        }
+    }
+
+    /// The names and values of the entries.
+    pub fn iter(&self) -> Iter<'_> {
+        Iter {
+            list: self,
+            indices: Indices::new(self.len()),
+        }
+    }
+
+    /// Appends an entry.
+    pub fn append(&mut self, name: &str, value: Option<&str>) -> Result<()> {
+        let name = c_string(name)?;
+        let value = value.map(c_string).transpose()?;
+        let value = value.as_ref().map_or(ptr::null(), |value| value.as_ptr());
+
+        check(unsafe { list::gp_list_append(self.as_ptr(), name.as_ptr(), value) })?;
+        Ok(())
+    }
+
+    /// The index of the first entry named `name`.
+    pub fn find_by_name(&self, name: &str) -> Option<usize> {
+        let name = c_string(name).ok()?;
+        let mut index = 0;
+
+        check(unsafe { list::gp_list_find_by_name(self.as_ptr(), &mut index, name.as_ptr()) })
+            .ok()?;
+        Some(index as usize)
+    }
+
+    /// Sorts the entries by name.
+    pub fn sort(&mut self) -> Result<()> {
+        check(unsafe { list::gp_list_sort(self.as_ptr()) })?;
+        Ok(())
+    }
+
+    /// Removes all entries.
+    pub fn reset(&mut self) -> Result<()> {
+        check(unsafe { list::gp_list_reset(self.as_ptr()) })?;
+        Ok(())
+    }
+}
+
+impl Drop for CameraList {
+    fn drop(&mut self) {
+        unsafe { list::gp_list_unref(self.as_ptr()) };
+    }
+}
+
+impl fmt::Debug for CameraList {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl FromIterator<(String, String)> for CameraList {
+    /// Collects name and value pairs into a list.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the list cannot be created, or a name or value contains a
+    /// NUL byte.
+    fn from_iter<I: IntoIterator<Item = (String, String)>>(iter: I) -> Self {
+        let mut list = CameraList::new().expect("could not create a CameraList");
+
+        for (name, value) in iter {
+            list.append(&name, Some(&value))
+                .expect("could not append to a CameraList");
+        }
+        list
+    }
+}
+
+impl<'a> IntoIterator for &'a CameraList {
+    type Item = (Cow<'a, str>, Option<Cow<'a, str>>);
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl IntoIterator for CameraList {
+    type Item = (String, Option<String>);
+    type IntoIter = IntoIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter {
+            indices: Indices::new(self.len()),
+            list: self,
+        }
+    }
+}
+
+/// Iterator over the entries of a [`CameraList`].
+#[derive(Debug, Clone)]
+pub struct Iter<'a> {
+    list: &'a CameraList,
+    indices: Indices,
+}
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = (Cow<'a, str>, Option<Cow<'a, str>>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let list = self.list;
+        self.indices.next(|index| list.get(index))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.indices.size_hint()
+    }
+}
+
+impl ExactSizeIterator for Iter<'_> {}
+
+impl FusedIterator for Iter<'_> {}
+
+/// Owning iterator over the entries of a [`CameraList`].
+#[derive(Debug)]
+pub struct IntoIter {
+    list: CameraList,
+    indices: Indices,
+}
+
+impl Iterator for IntoIter {
+    type Item = (String, Option<String>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.indices.next(|index| {
+            let (name, value) = self.list.get(index)?;
+            Some((name.into_owned(), value.map(Cow::into_owned)))
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.indices.size_hint()
+    }
+}
+
+impl ExactSizeIterator for IntoIter {}
+
+impl FusedIterator for IntoIter {}
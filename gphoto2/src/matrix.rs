@@ -0,0 +1,323 @@
+//! Exporting the models supported by the installed camera drivers.
+//!
+//! A [`SupportMatrix`] lists every model with its driver status, device
+//! type, port types, USB ids, driver and supported operations, and writes
+//! them as JSON, CSV or a Markdown table. Models are sorted and driver paths
+//! are reduced to the driver name, so the output of two libgphoto2 releases
+//! can be diffed.
+
+use std::{
+    fmt::Write as _,
+    io::{self, Write},
+    path::Path,
+};
+
+use libgphoto2_sys::{
+    abilities_list::{
+        CameraDriverStatus, CameraFileOperation, CameraFolderOperation, CameraOperation,
+        GphotoDeviceType,
+    },
+    port_info_list::GPPortType,
+};
+
+use crate::{
+    abilities::{Abilities, AbilitiesList},
+    context::Context,
+    Result,
+};
+
+/// An output format of [`SupportMatrix::write`].
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum Format {
+    /// An array with an object per model.
+    Json,
+    /// A header line and a line per model, lists are separated by `;`.
+    Csv,
+    /// A table with a row per model, lists are separated by `, `.
+    Markdown,
+}
+
+const PORTS: &[(GPPortType, &str)] = &[
+    (GPPortType::GP_PORT_SERIAL, "serial"),
+    (GPPortType::GP_PORT_USB, "usb"),
+    (GPPortType::GP_PORT_DISK, "disk"),
+    (GPPortType::GP_PORT_PTPIP, "ptpip"),
+    (GPPortType::GP_PORT_USB_DISK_DIRECT, "usbdiskdirect"),
+    (GPPortType::GP_PORT_USB_SCSI, "usbscsi"),
+    (GPPortType::GP_PORT_IP, "ip"),
+];
+
+const OPERATIONS: &[(CameraOperation, &str)] = &[
+    (CameraOperation::GP_OPERATION_CAPTURE_IMAGE, "capture_image"),
+    (CameraOperation::GP_OPERATION_CAPTURE_VIDEO, "capture_video"),
+    (CameraOperation::GP_OPERATION_CAPTURE_AUDIO, "capture_audio"),
+    (
+        CameraOperation::GP_OPERATION_CAPTURE_PREVIEW,
+        "capture_preview",
+    ),
+    (CameraOperation::GP_OPERATION_CONFIG, "config"),
+    (
+        CameraOperation::GP_OPERATION_TRIGGER_CAPTURE,
+        "trigger_capture",
+    ),
+];
+
+const FILE_OPERATIONS: &[(CameraFileOperation, &str)] = &[
+    (CameraFileOperation::GP_FILE_OPERATION_DELETE, "delete"),
+    (CameraFileOperation::GP_FILE_OPERATION_PREVIEW, "preview"),
+    (CameraFileOperation::GP_FILE_OPERATION_RAW, "raw"),
+    (CameraFileOperation::GP_FILE_OPERATION_AUDIO, "audio"),
+    (CameraFileOperation::GP_FILE_OPERATION_EXIF, "exif"),
+];
+
+const FOLDER_OPERATIONS: &[(CameraFolderOperation, &str)] = &[
+    (
+        CameraFolderOperation::GP_FOLDER_OPERATION_DELETE_ALL,
+        "delete_all",
+    ),
+    (
+        CameraFolderOperation::GP_FOLDER_OPERATION_PUT_FILE,
+        "put_file",
+    ),
+    (
+        CameraFolderOperation::GP_FOLDER_OPERATION_MAKE_DIR,
+        "make_dir",
+    ),
+    (
+        CameraFolderOperation::GP_FOLDER_OPERATION_REMOVE_DIR,
+        "remove_dir",
+    ),
+];
+
+/// The names of the flags of `names` that are set.
+fn flag_names<F: Copy>(
+    names: &[(F, &'static str)],
+    is_set: impl Fn(F) -> bool,
+) -> Vec<&'static str> {
+    names
+        .iter()
+        .filter(|&&(flag, _)| is_set(flag))
+        .map(|&(_, name)| name)
+        .collect()
+}
+
+fn status_name(status: CameraDriverStatus) -> &'static str {
+    match status {
+        CameraDriverStatus::GP_DRIVER_STATUS_PRODUCTION => "production",
+        CameraDriverStatus::GP_DRIVER_STATUS_TESTING => "testing",
+        CameraDriverStatus::GP_DRIVER_STATUS_EXPERIMENTAL => "experimental",
+        CameraDriverStatus::GP_DRIVER_STATUS_DEPRECATED => "deprecated",
+    }
+}
+
+fn device_type_name(device_type: GphotoDeviceType) -> &'static str {
+    match device_type {
+        GphotoDeviceType::GP_DEVICE_STILL_CAMERA => "still_camera",
+        GphotoDeviceType::GP_DEVICE_AUDIO_PLAYER => "audio_player",
+    }
+}
+
+/// The value of a column.
+enum Value {
+    Text(String),
+    /// A USB id, unset when 0.
+    Hex(i32),
+    /// A USB class, subclass or protocol code, where -1 matches any.
+    Code(Option<i32>),
+    List(Vec<&'static str>),
+}
+
+const COLUMNS: [&str; 13] = [
+    "model",
+    "status",
+    "device_type",
+    "ports",
+    "usb_vendor",
+    "usb_product",
+    "usb_class",
+    "usb_subclass",
+    "usb_protocol",
+    "library",
+    "operations",
+    "file_operations",
+    "folder_operations",
+];
+
+/// The values of the columns for `abilities`, in the order of `COLUMNS`.
+fn row(abilities: &Abilities) -> [Value; 13] {
+    // e.g. /usr/lib/libgphoto2/2.5.30/ptp2.so, whose directory changes with every release
+    let library = Path::new(&abilities.library)
+        .file_stem()
+        .map_or_else(String::new, |stem| stem.to_string_lossy().into_owned());
+    // Models matched by USB id have no class, while 0 is a valid subclass and protocol
+    let code = |code| (abilities.usb_class != 0).then_some(code);
+
+    [
+        Value::Text(abilities.model.clone()),
+        Value::Text(status_name(abilities.status).to_string()),
+        Value::Text(device_type_name(abilities.device_type).to_string()),
+        Value::List(flag_names(PORTS, |port| abilities.port.contains(port))),
+        Value::Hex(abilities.usb_vendor),
+        Value::Hex(abilities.usb_product),
+        Value::Code(code(abilities.usb_class)),
+        Value::Code(code(abilities.usb_subclass)),
+        Value::Code(code(abilities.usb_protocol)),
+        Value::Text(library),
+        Value::List(flag_names(OPERATIONS, |operation| {
+            abilities.operations.contains(operation)
+        })),
+        Value::List(flag_names(FILE_OPERATIONS, |operation| {
+            abilities.file_operations.contains(operation)
+        })),
+        Value::List(flag_names(FOLDER_OPERATIONS, |operation| {
+            abilities.folder_operations.contains(operation)
+        })),
+    ]
+}
+
+impl Value {
+    /// The value as a cell of a CSV or Markdown table.
+    fn cell(&self, separator: &str) -> String {
+        match *self {
+            Value::Text(ref text) => text.clone(),
+            Value::Hex(0) | Value::Code(None) => String::new(),
+            Value::Hex(id) => format!("{:04x}", id),
+            Value::Code(Some(-1)) => "*".to_string(),
+            Value::Code(Some(code)) => code.to_string(),
+            Value::List(ref names) => names.join(separator),
+        }
+    }
+
+    /// The value as JSON.
+    fn json(&self) -> String {
+        match *self {
+            Value::Text(ref text) => json_string(text),
+            Value::Hex(0) | Value::Code(None) => "null".to_string(),
+            Value::Hex(id) => format!("\"{:04x}\"", id),
+            Value::Code(Some(-1)) => "\"*\"".to_string(),
+            Value::Code(Some(code)) => code.to_string(),
+            Value::List(ref names) => {
+                let names: Vec<_> = names.iter().map(|name| json_string(name)).collect();
+                format!("[{}]", names.join(", "))
+            }
+        }
+    }
+}
+
+/// `s` as a JSON string.
+fn json_string(s: &str) -> String {
+    let mut json = String::with_capacity(s.len() + 2);
+    json.push('"');
+
+    for c in s.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if c.is_control() => {
+                let _ = write!(json, "\\u{:04x}", c as u32);
+            }
+            c => json.push(c),
+        }
+    }
+
+    json.push('"');
+    json
+}
+
+/// `s` as a CSV field, quoted if needed.
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+/// `s` as a Markdown table cell.
+fn markdown_cell(s: &str) -> String {
+    s.replace('|', "\\|").replace(['\n', '\r'], " ")
+}
+
+/// The camera models supported by a set of camera drivers.
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct SupportMatrix {
+    models: Vec<Abilities>,
+}
+
+impl SupportMatrix {
+    /// Loads the models supported by the camera drivers libgphoto2 finds.
+    pub fn load(context: &Context) -> Result<Self> {
+        Ok(AbilitiesList::load(context)?.iter().collect())
+    }
+
+    /// The models, sorted by name and driver.
+    pub fn models(&self) -> &[Abilities] {
+        &self.models
+    }
+
+    /// Writes the models in `format`.
+    pub fn write(&self, format: Format, mut writer: impl Write) -> io::Result<()> {
+        match format {
+            Format::Json => self.write_json(&mut writer),
+            Format::Csv => self.write_csv(&mut writer),
+            Format::Markdown => self.write_markdown(&mut writer),
+        }
+    }
+
+    fn write_json(&self, writer: &mut impl Write) -> io::Result<()> {
+        if self.models.is_empty() {
+            return writeln!(writer, "[]");
+        }
+
+        writeln!(writer, "[")?;
+        for (i, abilities) in self.models.iter().enumerate() {
+            writeln!(writer, "  {{")?;
+            for (j, (column, value)) in COLUMNS.iter().zip(row(abilities)).enumerate() {
+                let comma = if j + 1 < COLUMNS.len() { "," } else { "" };
+                writeln!(writer, "    \"{}\": {}{}", column, value.json(), comma)?;
+            }
+            let comma = if i + 1 < self.models.len() { "," } else { "" };
+            writeln!(writer, "  }}{}", comma)?;
+        }
+        writeln!(writer, "]")
+    }
+
+    fn write_csv(&self, writer: &mut impl Write) -> io::Result<()> {
+        writeln!(writer, "{}", COLUMNS.join(","))?;
+
+        for abilities in &self.models {
+            let fields: Vec<_> = row(abilities)
+                .iter()
+                .map(|value| csv_field(&value.cell(";")))
+                .collect();
+            writeln!(writer, "{}", fields.join(","))?;
+        }
+        Ok(())
+    }
+
+    fn write_markdown(&self, writer: &mut impl Write) -> io::Result<()> {
+        writeln!(writer, "| {} |", COLUMNS.join(" | "))?;
+        writeln!(writer, "|{}", " --- |".repeat(COLUMNS.len()))?;
+
+        for abilities in &self.models {
+            let cells: Vec<_> = row(abilities)
+                .iter()
+                .map(|value| markdown_cell(&value.cell(", ")))
+                .collect();
+            writeln!(writer, "| {} |", cells.join(" | "))?;
+        }
+        Ok(())
+    }
+}
+
+impl FromIterator<Abilities> for SupportMatrix {
+    fn from_iter<I: IntoIterator<Item = Abilities>>(iter: I) -> Self {
+        let mut models: Vec<_> = iter.into_iter().collect();
+        models.sort_by(|a, b| (&a.model, &a.library).cmp(&(&b.model, &b.library)));
+
+        SupportMatrix { models }
+    }
+}
@@ -0,0 +1,132 @@
+//! Access to the port a camera is connected through.
+
+use libc::{c_char, c_int};
+use libgphoto2_sys::port;
+
+use crate::{check, util::read_c_str, Result};
+
+/// A borrowed `GPPort`, e.g. the port of a camera handed to a camera driver.
+///
+/// The port is owned by libgphoto2, which opens and closes it.
+pub struct Port {
+    ptr: *mut port::GPPort,
+}
+
+/// The length of `buf` as a `c_int`, clamped to `c_int::MAX`.
+fn c_len(buf: &[u8]) -> c_int {
+    buf.len().min(c_int::MAX as usize) as c_int
+}
+
+impl Port {
+    /// Wraps `ptr`.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must be a valid port for as long as the `Port` is used.
+    pub unsafe fn from_raw(ptr: *mut port::GPPort) -> Self {
+        Port { ptr }
+    }
+
+    /// The wrapped `GPPort`.
+    pub fn as_ptr(&self) -> *mut port::GPPort {
+        self.ptr
+    }
+
+    /// Reads into `buf`, returning the number of bytes read.
+    pub fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        let len = c_len(buf);
+        let read =
+            check(unsafe { port::gp_port_read(self.ptr, buf.as_mut_ptr() as *mut c_char, len) })?;
+
+        Ok(read as usize)
+    }
+
+    /// Writes `data`, returning the number of bytes written.
+    pub fn write(&mut self, data: &[u8]) -> Result<usize> {
+        let len = c_len(data);
+        let written =
+            check(unsafe { port::gp_port_write(self.ptr, data.as_ptr() as *const c_char, len) })?;
+
+        Ok(written as usize)
+    }
+
+    /// Reads from the interrupt endpoint into `buf`, returning the number of
+    /// bytes read.
+    pub fn check_int(&mut self, buf: &mut [u8]) -> Result<usize> {
+        let len = c_len(buf);
+        let read = check(unsafe {
+            port::gp_port_check_int(self.ptr, buf.as_mut_ptr() as *mut c_char, len)
+        })?;
+
+        Ok(read as usize)
+    }
+
+    /// The timeout of reads and writes in milliseconds.
+    pub fn timeout(&self) -> Result<i32> {
+        let mut timeout = 0;
+        check(unsafe { port::gp_port_get_timeout(self.ptr, &mut timeout) })?;
+
+        Ok(timeout)
+    }
+
+    /// Sets the timeout of reads and writes in milliseconds.
+    pub fn set_timeout(&mut self, timeout: i32) -> Result<()> {
+        check(unsafe { port::gp_port_set_timeout(self.ptr, timeout) })?;
+
+        Ok(())
+    }
+
+    /// Sends a USB control message to the device and reads the response into
+    /// `buf`, returning the number of bytes read.
+    pub fn usb_msg_read(
+        &mut self,
+        request: i32,
+        value: i32,
+        index: i32,
+        buf: &mut [u8],
+    ) -> Result<usize> {
+        let len = c_len(buf);
+        let read = check(unsafe {
+            port::gp_port_usb_msg_read(
+                self.ptr,
+                request,
+                value,
+                index,
+                buf.as_mut_ptr() as *mut c_char,
+                len,
+            )
+        })?;
+
+        Ok(read as usize)
+    }
+
+    /// Sends a USB control message with `data` to the device, returning the
+    /// number of bytes written.
+    pub fn usb_msg_write(
+        &mut self,
+        request: i32,
+        value: i32,
+        index: i32,
+        data: &[u8],
+    ) -> Result<usize> {
+        let len = c_len(data);
+        // libgphoto2 does not modify the buffer of a write
+        let written = check(unsafe {
+            port::gp_port_usb_msg_write(
+                self.ptr,
+                request,
+                value,
+                index,
+                data.as_ptr() as *mut c_char,
+                len,
+            )
+        })?;
+
+        Ok(written as usize)
+    }
+
+    /// The last error message of the port, if any.
+    pub fn error(&self) -> Option<String> {
+        unsafe { read_c_str(port::gp_port_get_error(self.ptr)) }
+    }
+}
@@ -0,0 +1,196 @@
+//! Progress of long operations such as downloads and uploads.
+//!
+//! libgphoto2 reports progress through three context callbacks: one when an
+//! operation starts, with a target value (usually a size in bytes) and a
+//! description, one whenever the current value changes and one when the
+//! operation is done. A [`ProgressTracker`] turns them into
+//! [`ProgressEvent`]s and keeps a [`ProgressTask`] per running operation,
+//! from which fraction, throughput and remaining time are computed. Register
+//! one with
+//! [`Context::on_progress_events`](crate::context::Context::on_progress_events):
+//!
+//! ```ignore
+//! use gphoto2::{context::Context, progress::ProgressEvent};
+//!
+//! let context = Context::new()?.on_progress_events(|event, task| {
+//!     if let ProgressEvent::Updated { .. } = event {
+//!         println!("{}: {:.0}%", task.text, task.fraction() * 100.0);
+//!     }
+//! });
+//! ```
+
+use std::{
+    collections::BTreeMap,
+    time::{Duration, Instant},
+};
+
+/// A change in the progress of an operation.
+#[derive(Debug, PartialEq, Clone)]
+pub enum ProgressEvent {
+    /// An operation started.
+    Started {
+        /// The id of the operation.
+        id: u32,
+        /// The value `current` reaches when the operation is done.
+        target: f32,
+        /// A description of the operation.
+        text: String,
+    },
+    /// An operation made progress.
+    Updated {
+        /// The id of the operation.
+        id: u32,
+        /// The current value, between 0 and the target.
+        current: f32,
+    },
+    /// An operation is done.
+    Stopped {
+        /// The id of the operation.
+        id: u32,
+    },
+}
+
+/// The state of an operation that reports its progress.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ProgressTask {
+    /// The id of the operation.
+    pub id: u32,
+    /// The operation that was running when this one started, if any.
+    pub parent: Option<u32>,
+    /// The value `current` reaches when the operation is done.
+    pub target: f32,
+    /// The current value.
+    pub current: f32,
+    /// A description of the operation.
+    pub text: String,
+    /// When the operation started.
+    pub started: Instant,
+    /// When the current value was last updated.
+    pub updated: Instant,
+}
+
+impl ProgressTask {
+    /// The completed fraction of the operation, between 0 and 1.
+    pub fn fraction(&self) -> f32 {
+        if self.target > 0.0 {
+            (self.current / self.target).clamp(0.0, 1.0)
+        } else {
+            0.0
+        }
+    }
+
+    /// The time between the start and the last update.
+    pub fn elapsed(&self) -> Duration {
+        self.updated.saturating_duration_since(self.started)
+    }
+
+    /// The average progress per second, e.g. bytes per second for transfers.
+    pub fn throughput(&self) -> f32 {
+        let elapsed = self.elapsed().as_secs_f32();
+
+        if elapsed > 0.0 {
+            self.current / elapsed
+        } else {
+            0.0
+        }
+    }
+
+    /// The estimated time until the operation is done at the current
+    /// throughput, unknown until progress has been made.
+    pub fn eta(&self) -> Option<Duration> {
+        let throughput = self.throughput();
+        if throughput <= 0.0 {
+            return None;
+        }
+
+        let remaining = (self.target - self.current).max(0.0);
+        Duration::try_from_secs_f32(remaining / throughput).ok()
+    }
+}
+
+/// Tracks the operations reported by the progress callbacks of a context.
+///
+/// Operations may be nested, e.g. the download of each file of a folder.
+/// Each one gets a new id, and the innermost running operation becomes the
+/// parent of the next one started.
+#[derive(Debug, Default, Clone)]
+pub struct ProgressTracker {
+    tasks: BTreeMap<u32, ProgressTask>,
+    last_id: u32,
+}
+
+impl ProgressTracker {
+    /// Creates a tracker without running operations.
+    pub fn new() -> Self {
+        ProgressTracker::default()
+    }
+
+    /// The running operation with the id `id`.
+    pub fn task(&self, id: u32) -> Option<&ProgressTask> {
+        self.tasks.get(&id)
+    }
+
+    /// The running operations, outermost first.
+    pub fn tasks(&self) -> impl Iterator<Item = &ProgressTask> {
+        self.tasks.values()
+    }
+
+    /// Starts an operation.
+    pub fn start(&mut self, target: f32, text: &str) -> (ProgressEvent, &ProgressTask) {
+        self.start_at(Instant::now(), target, text)
+    }
+
+    /// Updates the current value of the operation `id`, if it is running.
+    pub fn update(&mut self, id: u32, current: f32) -> Option<(ProgressEvent, &ProgressTask)> {
+        self.update_at(Instant::now(), id, current)
+    }
+
+    /// Stops the operation `id`, returning its final state if it was running.
+    pub fn stop(&mut self, id: u32) -> Option<(ProgressEvent, ProgressTask)> {
+        let task = self.tasks.remove(&id)?;
+
+        Some((ProgressEvent::Stopped { id }, task))
+    }
+
+    pub(crate) fn start_at(
+        &mut self,
+        now: Instant,
+        target: f32,
+        text: &str,
+    ) -> (ProgressEvent, &ProgressTask) {
+        // 0 is what libgphoto2 uses when no progress callbacks are set
+        self.last_id = self.last_id.checked_add(1).unwrap_or(1);
+        let id = self.last_id;
+
+        let task = ProgressTask {
+            id,
+            parent: self.tasks.keys().next_back().copied(),
+            target,
+            current: 0.0,
+            text: text.to_string(),
+            started: now,
+            updated: now,
+        };
+        let event = ProgressEvent::Started {
+            id,
+            target,
+            text: task.text.clone(),
+        };
+
+        self.tasks.insert(id, task);
+        (event, &self.tasks[&id])
+    }
+
+    pub(crate) fn update_at(
+        &mut self,
+        now: Instant,
+        id: u32,
+        current: f32,
+    ) -> Option<(ProgressEvent, &ProgressTask)> {
+        let task = self.tasks.get_mut(&id)?;
+        task.current = current;
+        task.updated = now;
+
+        Some((ProgressEvent::Updated { id, current }, task))
+    }
+}
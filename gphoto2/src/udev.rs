@@ -0,0 +1,321 @@
+//! Generating udev rules and hwdb files granting access to cameras.
+//!
+//! [`UdevGenerator`] collects the USB ids of the models in an abilities
+//! list, and the USB interface classes matched by generic drivers such as
+//! PTP. It writes them as udev rules that tag cameras with the `ID_GPHOTO2`
+//! and `GPHOTO2_DRIVER` properties and set their permissions, or as a
+//! systemd hwdb file setting the properties of the models, together with the
+//! rules matching the interface classes and setting the permissions of
+//! devices with `ID_GPHOTO2`:
+//!
+//! ```ignore
+//! use gphoto2::{context::Context, udev::UdevGenerator};
+//!
+//! let generator = UdevGenerator::load(&Context::new()?)?
+//!     .group("camera")?
+//!     .mode("0660")?
+//!     .tag("uaccess")?;
+//! generator.write_rules(File::create("/etc/udev/rules.d/40-gphoto2.rules")?)?;
+//! ```
+
+use std::{
+    collections::HashSet,
+    io::{self, Write},
+};
+
+use libgphoto2_sys::abilities_list::GphotoDeviceType;
+
+use crate::{
+    abilities::{Abilities, AbilitiesList},
+    context::Context,
+    GpError, Result,
+};
+
+/// The label the rules skip to for devices they do not handle.
+const END_LABEL: &str = "gphoto2_rules_end";
+
+/// A USB device matched by vendor and product id.
+#[derive(Debug, PartialEq, Eq, Clone)]
+struct Device {
+    model: String,
+    vendor: u16,
+    product: u16,
+    driver: &'static str,
+    media_player: bool,
+}
+
+/// USB interfaces matched by class, subclass and protocol, where a missing
+/// subclass or protocol matches any.
+#[derive(Debug, PartialEq, Eq, Clone)]
+struct Class {
+    model: String,
+    class: u8,
+    subclass: Option<u8>,
+    protocol: Option<u8>,
+    driver: &'static str,
+}
+
+impl Class {
+    /// The `ID_USB_INTERFACES` pattern matching the interfaces.
+    fn interfaces(&self) -> String {
+        let code = |code: Option<u8>| {
+            code.map_or_else(|| "??".to_string(), |code| format!("{:02x}", code))
+        };
+
+        format!(
+            "*:{:02x}{}{}:*",
+            self.class,
+            code(self.subclass),
+            code(self.protocol)
+        )
+    }
+
+    /// The rule setting the properties of devices with the interfaces,
+    /// followed by `permissions`.
+    fn rule(&self, permissions: &str) -> String {
+        format!(
+            "ENV{{ID_USB_INTERFACES}}==\"{}\", ENV{{ID_GPHOTO2}}=\"1\", \
+             ENV{{GPHOTO2_DRIVER}}=\"{}\"{}",
+            self.interfaces(),
+            self.driver,
+            permissions
+        )
+    }
+}
+
+/// Whether `name` may be used as a group or tag, which must not end the
+/// quoted values of the rules early.
+fn is_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b == b'_' || b == b'-')
+}
+
+/// The value of `GPHOTO2_DRIVER` for a model, `PTP` for models handled by
+/// the PTP driver, which desktop environments may access directly.
+fn driver(abilities: &Abilities) -> &'static str {
+    if abilities.id == "PTP" {
+        "PTP"
+    } else {
+        "proprietary"
+    }
+}
+
+/// Generates udev rules and hwdb files from the USB ids of camera models.
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct UdevGenerator {
+    devices: Vec<Device>,
+    classes: Vec<Class>,
+    group: Option<String>,
+    mode: Option<String>,
+    tag: Option<String>,
+}
+
+impl UdevGenerator {
+    /// Collects the USB ids of the models supported by the camera drivers
+    /// libgphoto2 finds.
+    pub fn load(context: &Context) -> Result<Self> {
+        Ok(AbilitiesList::load(context)?.iter().collect())
+    }
+
+    /// Assigns matching devices to the group `group`, e.g. `plugdev`.
+    ///
+    /// Fails with [`GpError::BadParameters`] unless `group` consists of
+    /// ASCII letters, digits, `_` and `-`.
+    pub fn group(mut self, group: &str) -> Result<Self> {
+        if !is_name(group) {
+            return Err(GpError::BadParameters);
+        }
+        self.group = Some(group.to_string());
+        Ok(self)
+    }
+
+    /// Sets the permissions of matching devices to `mode`, e.g. `0664`.
+    ///
+    /// Fails with [`GpError::BadParameters`] unless `mode` consists of octal
+    /// digits.
+    pub fn mode(mut self, mode: &str) -> Result<Self> {
+        if mode.is_empty() || !mode.bytes().all(|b| matches!(b, b'0'..=b'7')) {
+            return Err(GpError::BadParameters);
+        }
+        self.mode = Some(mode.to_string());
+        Ok(self)
+    }
+
+    /// Adds `tag` to the tags of matching devices, e.g. `uaccess` to grant
+    /// access to the logged in user.
+    ///
+    /// Fails with [`GpError::BadParameters`] unless `tag` consists of ASCII
+    /// letters, digits, `_` and `-`.
+    pub fn tag(mut self, tag: &str) -> Result<Self> {
+        if !is_name(tag) {
+            return Err(GpError::BadParameters);
+        }
+        self.tag = Some(tag.to_string());
+        Ok(self)
+    }
+
+    /// The assignments setting the permissions of a device.
+    fn permissions(&self) -> String {
+        let mut assignments = String::new();
+
+        if let Some(mode) = &self.mode {
+            assignments += &format!(", MODE=\"{}\"", mode);
+        }
+        if let Some(group) = &self.group {
+            assignments += &format!(", GROUP=\"{}\"", group);
+        }
+        if let Some(tag) = &self.tag {
+            assignments += &format!(", TAG+=\"{}\"", tag);
+        }
+        assignments
+    }
+
+    /// Writes udev rules setting the properties and permissions of every
+    /// supported device.
+    pub fn write_rules(&self, mut writer: impl Write) -> io::Result<()> {
+        let permissions = self.permissions();
+
+        writeln!(
+            writer,
+            "# udev rules for the cameras supported by libgphoto2"
+        )?;
+        writeln!(writer, "ACTION==\"remove\", GOTO=\"{}\"", END_LABEL)?;
+        writeln!(writer, "SUBSYSTEM!=\"usb\", GOTO=\"{}\"", END_LABEL)?;
+        writeln!(
+            writer,
+            "ENV{{DEVTYPE}}!=\"usb_device\", GOTO=\"{}\"",
+            END_LABEL
+        )?;
+
+        for class in &self.classes {
+            writeln!(writer)?;
+            writeln!(writer, "# {}", class.model)?;
+            writeln!(writer, "{}", class.rule(&permissions))?;
+        }
+
+        for device in &self.devices {
+            let media_player = if device.media_player {
+                ", ENV{ID_MEDIA_PLAYER}=\"1\""
+            } else {
+                ""
+            };
+
+            writeln!(writer)?;
+            writeln!(writer, "# {}", device.model)?;
+            writeln!(
+                writer,
+                "ATTR{{idVendor}}==\"{:04x}\", ATTR{{idProduct}}==\"{:04x}\", \
+                 ENV{{ID_GPHOTO2}}=\"1\", ENV{{GPHOTO2_DRIVER}}=\"{}\"{}{}",
+                device.vendor, device.product, device.driver, media_player, permissions
+            )?;
+        }
+
+        writeln!(writer)?;
+        writeln!(writer, "LABEL=\"{}\"", END_LABEL)
+    }
+
+    /// Writes a systemd hwdb file setting the properties of every model with
+    /// USB ids, for use with [`UdevGenerator::write_hwdb_rules`].
+    ///
+    /// Interface classes are matched by the rules instead, as the modalias
+    /// hwdb looks up for a USB device does not contain them.
+    pub fn write_hwdb(&self, mut writer: impl Write) -> io::Result<()> {
+        writeln!(
+            writer,
+            "# hwdb entries for the cameras supported by libgphoto2"
+        )?;
+
+        for device in &self.devices {
+            writeln!(writer)?;
+            writeln!(writer, "# {}", device.model)?;
+            writeln!(writer, "usb:v{:04X}p{:04X}*", device.vendor, device.product)?;
+            writeln!(writer, " GPHOTO2_DRIVER={}", device.driver)?;
+            writeln!(writer, " ID_GPHOTO2=1")?;
+            if device.media_player {
+                writeln!(writer, " ID_MEDIA_PLAYER=1")?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes the udev rules matching the interface classes and setting the
+    /// permissions of the devices tagged by them or by the hwdb file of
+    /// [`UdevGenerator::write_hwdb`].
+    pub fn write_hwdb_rules(&self, mut writer: impl Write) -> io::Result<()> {
+        let permissions = self.permissions();
+
+        writeln!(
+            writer,
+            "# udev rules for the cameras in the libgphoto2 hwdb"
+        )?;
+        writeln!(writer, "ACTION==\"remove\", GOTO=\"{}\"", END_LABEL)?;
+        writeln!(writer, "SUBSYSTEM!=\"usb\", GOTO=\"{}\"", END_LABEL)?;
+        writeln!(
+            writer,
+            "ENV{{DEVTYPE}}!=\"usb_device\", GOTO=\"{}\"",
+            END_LABEL
+        )?;
+
+        for class in &self.classes {
+            writeln!(writer)?;
+            writeln!(writer, "# {}", class.model)?;
+            writeln!(writer, "{}", class.rule(&permissions))?;
+        }
+
+        writeln!(writer)?;
+        writeln!(writer, "IMPORT{{builtin}}=\"hwdb --subsystem=usb\"")?;
+        writeln!(writer, "ENV{{ID_GPHOTO2}}==\"1\"{}", permissions)?;
+        writeln!(writer)?;
+        writeln!(writer, "LABEL=\"{}\"", END_LABEL)
+    }
+}
+
+impl FromIterator<Abilities> for UdevGenerator {
+    /// Collects the USB ids of `abilities`, ignoring duplicates and models
+    /// without USB ids.
+    fn from_iter<I: IntoIterator<Item = Abilities>>(iter: I) -> Self {
+        let mut generator = UdevGenerator::default();
+        let mut devices = HashSet::new();
+        let mut classes = HashSet::new();
+
+        for abilities in iter {
+            let vendor = u16::try_from(abilities.usb_vendor).unwrap_or(0);
+            let product = u16::try_from(abilities.usb_product).unwrap_or(0);
+
+            if vendor != 0 && product != 0 {
+                if devices.insert((vendor, product)) {
+                    generator.devices.push(Device {
+                        model: abilities.model.clone(),
+                        vendor,
+                        product,
+                        driver: driver(&abilities),
+                        media_player: abilities.device_type
+                            == GphotoDeviceType::GP_DEVICE_AUDIO_PLAYER,
+                    });
+                }
+                continue;
+            }
+
+            // Drivers use class codes above 255 to match devices by other
+            // means, and -1 as the subclass or protocol to match any
+            if let Ok(class @ 1..) = u8::try_from(abilities.usb_class) {
+                let subclass = u8::try_from(abilities.usb_subclass).ok();
+                let protocol = u8::try_from(abilities.usb_protocol).ok();
+
+                if classes.insert((class, subclass, protocol)) {
+                    generator.classes.push(Class {
+                        model: abilities.model.clone(),
+                        class,
+                        subclass,
+                        protocol,
+                        driver: driver(&abilities),
+                    });
+                }
+            }
+        }
+
+        generator
+    }
+}
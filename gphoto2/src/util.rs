@@ -0,0 +1,128 @@
+//! Conversions between Rust strings and the C strings of libgphoto2, and
+//! helpers for functions called by libgphoto2.
+
+use std::{
+    ffi::{CStr, CString},
+    panic::{self, AssertUnwindSafe},
+};
+
+use libc::{c_char, c_int};
+use libgphoto2_sys::result::GP_ERROR_LIBRARY;
+
+use crate::{GpError, Result};
+
+/// A Rust string as a C string, failing on interior NULs.
+pub(crate) fn c_string(s: &str) -> Result<CString> {
+    CString::new(s).map_err(|_| GpError::BadParameters)
+}
+
+/// Copies `s` into the fixed size C string `dst`, truncating it to fit.
+pub(crate) fn write_c_array(dst: &mut [c_char], s: &str) {
+    let len = s.len().min(dst.len().saturating_sub(1));
+
+    for (dst, &byte) in dst.iter_mut().zip(&s.as_bytes()[..len]) {
+        *dst = byte as c_char;
+    }
+    if let Some(end) = dst.get_mut(len) {
+        *end = 0;
+    }
+}
+
+/// The contents of a fixed size C string, up to the first NUL.
+pub(crate) fn read_c_array(src: &[c_char]) -> String {
+    let bytes: Vec<u8> = src
+        .iter()
+        .take_while(|&&c| c != 0)
+        .map(|&c| c as u8)
+        .collect();
+
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+/// The string `ptr` points to, if it is not null.
+///
+/// # Safety
+///
+/// `ptr` must be null or point to a NUL terminated string.
+pub(crate) unsafe fn read_c_str(ptr: *const c_char) -> Option<String> {
+    if ptr.is_null() {
+        None
+    } else {
+        Some(CStr::from_ptr(ptr).to_string_lossy().into_owned())
+    }
+}
+
+/// Runs `f` for a function called by libgphoto2, returning `None` if it
+/// panics.
+pub(crate) fn catch_panic<R>(f: impl FnOnce() -> R) -> Option<R> {
+    // Unwinding into libgphoto2 is undefined behaviour
+    panic::catch_unwind(AssertUnwindSafe(f)).ok()
+}
+
+/// Runs `f` for a function called by libgphoto2, turning its result or a
+/// panic into a gphoto2 return value.
+pub(crate) fn ffi_guard(f: impl FnOnce() -> Result<c_int>) -> c_int {
+    match catch_panic(f) {
+        Some(Ok(ret)) => ret,
+        Some(Err(err)) => err.code(),
+        None => GP_ERROR_LIBRARY,
+    }
+}
+
+/// The position of an iterator over the entries of a libgphoto2 list, which
+/// are looked up by index.
+#[derive(Debug, Clone)]
+pub(crate) struct Indices {
+    index: usize,
+    len: usize,
+}
+
+impl Indices {
+    /// Positions before the first of `len` entries.
+    pub(crate) fn new(len: usize) -> Self {
+        Indices { index: 0, len }
+    }
+
+    /// The entry `get` returns for the next index.
+    ///
+    /// Iteration stops at the first entry that cannot be read rather than
+    /// skipping it, so the entries returned are always a prefix of the list.
+    pub(crate) fn next<T>(&mut self, get: impl FnOnce(usize) -> Option<T>) -> Option<T> {
+        if self.index >= self.len {
+            return None;
+        }
+
+        let entry = get(self.index);
+        self.index = if entry.is_some() {
+            self.index + 1
+        } else {
+            self.len
+        };
+        entry
+    }
+
+    /// The number of remaining entries, exactly.
+    pub(crate) fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.len - self.index;
+        (len, Some(len))
+    }
+}
+
+/// Frees a pointer returned by libgphoto2 when dropped.
+pub(crate) struct Guard<T, F: FnMut(*mut T)> {
+    pub(crate) ptr: *mut T,
+    free: F,
+}
+
+impl<T, F: FnMut(*mut T)> Guard<T, F> {
+    /// Calls `free` with `ptr` when dropped.
+    pub(crate) fn new(ptr: *mut T, free: F) -> Self {
+        Guard { ptr, free }
+    }
+}
+
+impl<T, F: FnMut(*mut T)> Drop for Guard<T, F> {
+    fn drop(&mut self) {
+        (self.free)(self.ptr);
+    }
+}
@@ -0,0 +1,556 @@
+//! Configuration widgets of cameras and camera drivers.
+//!
+//! The configuration of a camera is a tree of `CameraWidget`s: a window
+//! containing sections, which contain the settings. The type of the value
+//! `gp_widget_get_value` and `gp_widget_set_value` read and write depends on
+//! the type of the widget. [`Widget::kind`] reads it as a [`WidgetKind`], and
+//! the setters check the widget type before writing:
+//!
+//! ```ignore
+//! use gphoto2::widget::WidgetKind;
+//!
+//! let config = camera.config(&context)?;
+//! let iso = config.child_by_name("iso")?;
+//! if let WidgetKind::Radio { choices, .. } = iso.kind()? {
+//!     iso.set_choice(&choices[0])?;
+//! }
+//! camera.set_config(&context, &config)?;
+//! ```
+
+use std::{
+    fmt,
+    iter::FusedIterator,
+    mem::ManuallyDrop,
+    ptr,
+    ptr::NonNull,
+    rc::Rc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use libc::{c_char, c_float, c_int, c_void};
+use libgphoto2_sys::widget::{self, CameraWidget, CameraWidgetType};
+
+use crate::{
+    check,
+    util::{c_string, read_c_str, Indices},
+    GpError, Result,
+};
+
+/// The type and value of a widget.
+#[derive(Debug, PartialEq, Clone)]
+pub enum WidgetKind {
+    /// The top level widget, containing sections.
+    Window,
+    /// A group of widgets, e.g. a tab.
+    Section,
+    /// A text entry.
+    Text(String),
+    /// A slider.
+    Range {
+        /// The current value.
+        value: f32,
+        /// The smallest value.
+        min: f32,
+        /// The largest value.
+        max: f32,
+        /// The difference between neighbouring values.
+        step: f32,
+    },
+    /// A check box.
+    Toggle(bool),
+    /// A choice between a few values, e.g. radio buttons.
+    Radio {
+        /// The current value, usually one of `choices`.
+        value: String,
+        /// The values to choose from.
+        choices: Vec<String>,
+    },
+    /// A choice between many values, e.g. a drop down menu.
+    Menu {
+        /// The current value, usually one of `choices`.
+        value: String,
+        /// The values to choose from.
+        choices: Vec<String>,
+    },
+    /// A button running an action of the camera driver.
+    Button,
+    /// A date and time.
+    Date(SystemTime),
+}
+
+/// Unrefs the root of a widget tree when the last handle into it is dropped.
+struct Root {
+    ptr: NonNull<CameraWidget>,
+    /// Whether the caller owns the tree, rather than libgphoto2 lending it
+    /// to a camera driver, in which case it must not become part of another.
+    owned: bool,
+}
+
+impl Drop for Root {
+    fn drop(&mut self) {
+        unsafe { widget::gp_widget_unref(self.ptr.as_ptr()) };
+    }
+}
+
+/// A widget in a configuration tree.
+///
+/// A widget keeps the whole tree alive, and the widgets returned by
+/// [`Widget::children`], [`Widget::parent`] and [`Widget::root`] refer to
+/// the same tree, so changes made through one of them are seen by all.
+#[derive(Clone)]
+pub struct Widget {
+    ptr: NonNull<CameraWidget>,
+    root: Rc<Root>,
+}
+
+impl Widget {
+    /// Creates a widget of the type `widget_type` without children, which
+    /// becomes the root of a new tree.
+    pub fn new(widget_type: CameraWidgetType, label: &str) -> Result<Self> {
+        let label = c_string(label)?;
+        let mut ptr = ptr::null_mut();
+        check(unsafe { widget::gp_widget_new(widget_type, label.as_ptr(), &mut ptr) })?;
+
+        let ptr = NonNull::new(ptr).ok_or(GpError::NoMemory)?;
+        Ok(unsafe { Widget::from_root(ptr) })
+    }
+
+    /// Takes ownership of the tree `root`.
+    ///
+    /// # Safety
+    ///
+    /// `root` must be the root of a tree the caller holds a reference to,
+    /// which is released when the widget and its clones are dropped.
+    pub(crate) unsafe fn from_root(root: NonNull<CameraWidget>) -> Self {
+        Widget {
+            ptr: root,
+            root: Rc::new(Root {
+                ptr: root,
+                owned: true,
+            }),
+        }
+    }
+
+    /// A handle to `widget`, which libgphoto2 owns, that takes a reference
+    /// to its tree.
+    ///
+    /// # Safety
+    ///
+    /// `widget` must point to a valid `CameraWidget`.
+    pub(crate) unsafe fn from_borrowed(widget: NonNull<CameraWidget>) -> Self {
+        let mut root = ptr::null_mut();
+        widget::gp_widget_get_root(widget.as_ptr(), &mut root);
+        let root = NonNull::new(root).unwrap_or(widget);
+        widget::gp_widget_ref(root.as_ptr());
+
+        Widget {
+            ptr: widget,
+            root: Rc::new(Root {
+                ptr: root,
+                owned: false,
+            }),
+        }
+    }
+
+    /// Gives up ownership of the tree of the widget, returning its root.
+    ///
+    /// Fails with [`GpError::BadParameters`] unless the widget is the root of
+    /// a tree the caller owns and there are no other handles into that tree.
+    pub(crate) fn into_root(self) -> Result<NonNull<CameraWidget>> {
+        if self.ptr != self.root.ptr || !self.root.owned {
+            return Err(GpError::BadParameters);
+        }
+        let root = Rc::try_unwrap(self.root).map_err(|_| GpError::BadParameters)?;
+
+        Ok(ManuallyDrop::new(root).ptr)
+    }
+
+    /// A widget of the same tree.
+    fn node(&self, ptr: *mut CameraWidget) -> Option<Self> {
+        Some(Widget {
+            ptr: NonNull::new(ptr)?,
+            root: Rc::clone(&self.root),
+        })
+    }
+
+    /// The wrapped `CameraWidget`.
+    pub fn as_ptr(&self) -> *mut CameraWidget {
+        self.ptr.as_ptr()
+    }
+
+    /// The type of the widget.
+    pub fn widget_type(&self) -> CameraWidgetType {
+        let mut widget_type = CameraWidgetType::GP_WIDGET_WINDOW;
+        unsafe { widget::gp_widget_get_type(self.as_ptr(), &mut widget_type) };
+
+        widget_type
+    }
+
+    /// The id of the widget, unique within the process.
+    pub fn id(&self) -> i32 {
+        let mut id = 0;
+        unsafe { widget::gp_widget_get_id(self.as_ptr(), &mut id) };
+
+        id
+    }
+
+    /// The name of the widget, e.g. `iso`.
+    pub fn name(&self) -> String {
+        self.string(|ptr, name| unsafe { widget::gp_widget_get_name(ptr, name) })
+    }
+
+    /// The label shown for the widget, e.g. `ISO Speed`.
+    pub fn label(&self) -> String {
+        self.string(|ptr, label| unsafe { widget::gp_widget_get_label(ptr, label) })
+    }
+
+    /// A description of the widget.
+    pub fn info(&self) -> String {
+        self.string(|ptr, info| unsafe { widget::gp_widget_get_info(ptr, info) })
+    }
+
+    /// Returns whether the camera ignores changes to the widget.
+    pub fn is_readonly(&self) -> bool {
+        let mut readonly = 0;
+        unsafe { widget::gp_widget_get_readonly(self.as_ptr(), &mut readonly) };
+
+        readonly != 0
+    }
+
+    /// Sets the name of the widget.
+    pub fn set_name(&self, name: &str) -> Result<()> {
+        let name = c_string(name)?;
+        check(unsafe { widget::gp_widget_set_name(self.as_ptr(), name.as_ptr()) })?;
+        Ok(())
+    }
+
+    /// Sets the description of the widget.
+    pub fn set_info(&self, info: &str) -> Result<()> {
+        let info = c_string(info)?;
+        check(unsafe { widget::gp_widget_set_info(self.as_ptr(), info.as_ptr()) })?;
+        Ok(())
+    }
+
+    /// Sets whether the camera ignores changes to the widget.
+    pub fn set_readonly(&self, readonly: bool) -> Result<()> {
+        check(unsafe { widget::gp_widget_set_readonly(self.as_ptr(), readonly as c_int) })?;
+        Ok(())
+    }
+
+    /// The string `get` returns for the widget, empty on errors.
+    fn string(&self, get: impl FnOnce(*mut CameraWidget, *mut *const c_char) -> c_int) -> String {
+        let mut string = ptr::null();
+
+        match check(get(self.as_ptr(), &mut string)) {
+            Ok(_) => unsafe { read_c_str(string) }.unwrap_or_default(),
+            Err(_) => String::new(),
+        }
+    }
+
+    /// The type and value of the widget.
+    pub fn kind(&self) -> Result<WidgetKind> {
+        Ok(match self.widget_type() {
+            CameraWidgetType::GP_WIDGET_WINDOW => WidgetKind::Window,
+            CameraWidgetType::GP_WIDGET_SECTION => WidgetKind::Section,
+            CameraWidgetType::GP_WIDGET_TEXT => WidgetKind::Text(self.string_value()?),
+            CameraWidgetType::GP_WIDGET_RANGE => {
+                let mut value: c_float = 0.0;
+                let (mut min, mut max, mut step) = (0.0, 0.0, 0.0);
+
+                unsafe {
+                    self.get_value(&mut value)?;
+                    check(widget::gp_widget_get_range(
+                        self.as_ptr(),
+                        &mut min,
+                        &mut max,
+                        &mut step,
+                    ))?;
+                }
+                WidgetKind::Range {
+                    value,
+                    min,
+                    max,
+                    step,
+                }
+            }
+            CameraWidgetType::GP_WIDGET_TOGGLE => {
+                let mut value: c_int = 0;
+                unsafe { self.get_value(&mut value)? };
+
+                WidgetKind::Toggle(value != 0)
+            }
+            CameraWidgetType::GP_WIDGET_RADIO => WidgetKind::Radio {
+                value: self.string_value()?,
+                choices: self.choices()?,
+            },
+            CameraWidgetType::GP_WIDGET_MENU => WidgetKind::Menu {
+                value: self.string_value()?,
+                choices: self.choices()?,
+            },
+            CameraWidgetType::GP_WIDGET_BUTTON => WidgetKind::Button,
+            CameraWidgetType::GP_WIDGET_DATE => {
+                let mut value: c_int = 0;
+                unsafe { self.get_value(&mut value)? };
+
+                // Seconds since the epoch, widened before negating i32::MIN
+                let seconds = Duration::from_secs(i64::from(value).unsigned_abs());
+                WidgetKind::Date(if value >= 0 {
+                    UNIX_EPOCH + seconds
+                } else {
+                    UNIX_EPOCH - seconds
+                })
+            }
+        })
+    }
+
+    /// Reads the value of the widget into `value`.
+    ///
+    /// # Safety
+    ///
+    /// `T` must be the value type of the widget type.
+    unsafe fn get_value<T>(&self, value: &mut T) -> Result<()> {
+        check(widget::gp_widget_get_value(
+            self.as_ptr(),
+            (value as *mut T).cast::<c_void>(),
+        ))?;
+        Ok(())
+    }
+
+    /// The value of a text, radio or menu widget.
+    fn string_value(&self) -> Result<String> {
+        let mut value: *const c_char = ptr::null();
+        unsafe {
+            self.get_value(&mut value)?;
+            Ok(read_c_str(value).unwrap_or_default())
+        }
+    }
+
+    /// The choices of a radio or menu widget.
+    fn choices(&self) -> Result<Vec<String>> {
+        let count = check(unsafe { widget::gp_widget_count_choices(self.as_ptr()) })?;
+
+        (0..count)
+            .map(|index| {
+                let mut choice = ptr::null();
+                unsafe {
+                    check(widget::gp_widget_get_choice(
+                        self.as_ptr(),
+                        index,
+                        &mut choice,
+                    ))?;
+                    Ok(read_c_str(choice).unwrap_or_default())
+                }
+            })
+            .collect()
+    }
+
+    /// Fails with [`GpError::BadParameters`] unless the widget is of one of
+    /// the types `types`.
+    fn expect_type(&self, types: &[CameraWidgetType]) -> Result<()> {
+        if types.contains(&self.widget_type()) {
+            Ok(())
+        } else {
+            Err(GpError::BadParameters)
+        }
+    }
+
+    /// Sets the value of a text widget.
+    pub fn set_text(&self, text: &str) -> Result<()> {
+        self.expect_type(&[CameraWidgetType::GP_WIDGET_TEXT])?;
+        self.set_string_value(text)
+    }
+
+    /// Sets the value of a range widget.
+    pub fn set_range_value(&self, value: f32) -> Result<()> {
+        self.expect_type(&[CameraWidgetType::GP_WIDGET_RANGE])?;
+        let value: c_float = value;
+
+        check(unsafe {
+            widget::gp_widget_set_value(self.as_ptr(), (&value as *const c_float).cast())
+        })?;
+        Ok(())
+    }
+
+    /// Sets the value of a toggle widget.
+    pub fn set_toggle(&self, value: bool) -> Result<()> {
+        self.expect_type(&[CameraWidgetType::GP_WIDGET_TOGGLE])?;
+        self.set_int_value(value as c_int)
+    }
+
+    /// Sets the value of a radio or menu widget.
+    pub fn set_choice(&self, choice: &str) -> Result<()> {
+        self.expect_type(&[
+            CameraWidgetType::GP_WIDGET_RADIO,
+            CameraWidgetType::GP_WIDGET_MENU,
+        ])?;
+        self.set_string_value(choice)
+    }
+
+    /// Sets the value of a date widget.
+    ///
+    /// Fails with [`GpError::BadParameters`] for dates libgphoto2 cannot
+    /// represent, i.e. before 1901 or after 2038.
+    pub fn set_date(&self, date: SystemTime) -> Result<()> {
+        self.expect_type(&[CameraWidgetType::GP_WIDGET_DATE])?;
+
+        let seconds = match date.duration_since(UNIX_EPOCH) {
+            Ok(after) => i64::try_from(after.as_secs()),
+            Err(before) => i64::try_from(before.duration().as_secs()).map(|seconds| -seconds),
+        };
+        let seconds = seconds
+            .ok()
+            .and_then(|seconds| c_int::try_from(seconds).ok())
+            .ok_or(GpError::BadParameters)?;
+
+        self.set_int_value(seconds)
+    }
+
+    fn set_string_value(&self, value: &str) -> Result<()> {
+        let value = c_string(value)?;
+
+        // Strings are passed directly rather than through a pointer to them
+        check(unsafe { widget::gp_widget_set_value(self.as_ptr(), value.as_ptr().cast()) })?;
+        Ok(())
+    }
+
+    fn set_int_value(&self, value: c_int) -> Result<()> {
+        check(unsafe {
+            widget::gp_widget_set_value(self.as_ptr(), (&value as *const c_int).cast())
+        })?;
+        Ok(())
+    }
+
+    /// Sets the bounds and step of a range widget.
+    pub fn set_range(&self, min: f32, max: f32, step: f32) -> Result<()> {
+        check(unsafe { widget::gp_widget_set_range(self.as_ptr(), min, max, step) })?;
+        Ok(())
+    }
+
+    /// Adds a choice to a radio or menu widget.
+    pub fn add_choice(&self, choice: &str) -> Result<()> {
+        let choice = c_string(choice)?;
+        check(unsafe { widget::gp_widget_add_choice(self.as_ptr(), choice.as_ptr()) })?;
+        Ok(())
+    }
+
+    /// Appends `child` to the children of a window or section.
+    ///
+    /// `child` must be the root of a tree the caller owns, and there must be
+    /// no other handles into that tree, which becomes part of this one. Fails with
+    /// [`GpError::BadParameters`] otherwise, or if this widget cannot have
+    /// children, in which case `child` is dropped.
+    pub fn append(&self, child: Widget) -> Result<()> {
+        let child = child.into_root()?;
+
+        // Freed together with the parent from now on, unless this fails
+        if let Err(err) = check(unsafe { widget::gp_widget_append(self.as_ptr(), child.as_ptr()) })
+        {
+            drop(Root {
+                ptr: child,
+                owned: true,
+            });
+            return Err(err);
+        }
+        Ok(())
+    }
+
+    /// The children of a window or section.
+    pub fn children(&self) -> Children<'_> {
+        let count = unsafe { widget::gp_widget_count_children(self.as_ptr()) };
+
+        Children {
+            widget: self,
+            indices: Indices::new(usize::try_from(count).unwrap_or(0)),
+        }
+    }
+
+    /// The child at `index` of a window or section.
+    pub fn child(&self, index: usize) -> Option<Widget> {
+        let index = c_int::try_from(index).ok()?;
+        let mut child = ptr::null_mut();
+        check(unsafe { widget::gp_widget_get_child(self.as_ptr(), index, &mut child) }).ok()?;
+
+        self.node(child)
+    }
+
+    /// The first widget named `name` in the subtree of this widget.
+    pub fn child_by_name(&self, name: &str) -> Result<Widget> {
+        let name = c_string(name)?;
+        let mut child = ptr::null_mut();
+        check(unsafe {
+            widget::gp_widget_get_child_by_name(self.as_ptr(), name.as_ptr(), &mut child)
+        })?;
+
+        self.node(child).ok_or(GpError::BadParameters)
+    }
+
+    /// The first widget labelled `label` in the subtree of this widget.
+    pub fn child_by_label(&self, label: &str) -> Result<Widget> {
+        let label = c_string(label)?;
+        let mut child = ptr::null_mut();
+        check(unsafe {
+            widget::gp_widget_get_child_by_label(self.as_ptr(), label.as_ptr(), &mut child)
+        })?;
+
+        self.node(child).ok_or(GpError::BadParameters)
+    }
+
+    /// The window or section containing the widget, if it is not the root.
+    pub fn parent(&self) -> Option<Widget> {
+        let mut parent = ptr::null_mut();
+        check(unsafe { widget::gp_widget_get_parent(self.as_ptr(), &mut parent) }).ok()?;
+
+        self.node(parent)
+    }
+
+    /// The root of the tree, usually a window.
+    pub fn root(&self) -> Widget {
+        Widget {
+            ptr: self.root.ptr,
+            root: Rc::clone(&self.root),
+        }
+    }
+}
+
+impl fmt::Debug for Widget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Widget")
+            .field("name", &self.name())
+            .field("label", &self.label())
+            .field("widget_type", &self.widget_type())
+            .finish()
+    }
+}
+
+impl<'a> IntoIterator for &'a Widget {
+    type Item = Widget;
+    type IntoIter = Children<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.children()
+    }
+}
+
+/// Iterator over the children of a [`Widget`].
+#[derive(Debug, Clone)]
+pub struct Children<'a> {
+    widget: &'a Widget,
+    indices: Indices,
+}
+
+impl Iterator for Children<'_> {
+    type Item = Widget;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let widget = self.widget;
+        self.indices.next(|index| widget.child(index))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.indices.size_hint()
+    }
+}
+
+impl ExactSizeIterator for Children<'_> {}
+
+impl FusedIterator for Children<'_> {}
@@ -31,58 +31,64 @@ pub enum GphotoDeviceType {
     GP_DEVICE_AUDIO_PLAYER = 1 << 0,
 }
 
-/// A bitmask of remote control related operations of the device.
-/// Some drivers might support additional dynamic capabilities (like the PTP driver).
-#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
-#[repr(C)]
-pub enum CameraOperation {
-    /// No remote control operation supported.
-    GP_OPERATION_NONE = 0,
-    /// Capturing images supported.
-    GP_OPERATION_CAPTURE_IMAGE = 1 << 0,
-    /// Capturing videos supported.
-    GP_OPERATION_CAPTURE_VIDEO = 1 << 1,
-    /// Capturing audio supported.
-    GP_OPERATION_CAPTURE_AUDIO = 1 << 2,
-    /// Capturing image previews supported.
-    GP_OPERATION_CAPTURE_PREVIEW = 1 << 3,
-    /// Camera and Driver configuration supported.
-    GP_OPERATION_CONFIG = 1 << 4,
-    /// Camera can trigger capture and wait for events.
-    GP_OPERATION_TRIGGER_CAPTURE = 1 << 5,
+bitflags! {
+    /// A bitmask of remote control related operations of the device.
+    /// Some drivers might support additional dynamic capabilities (like the PTP driver).
+    #[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+    #[repr(transparent)]
+    pub struct CameraOperation: c_int {
+        /// No remote control operation supported.
+        const GP_OPERATION_NONE = 0;
+        /// Capturing images supported.
+        const GP_OPERATION_CAPTURE_IMAGE = 1 << 0;
+        /// Capturing videos supported.
+        const GP_OPERATION_CAPTURE_VIDEO = 1 << 1;
+        /// Capturing audio supported.
+        const GP_OPERATION_CAPTURE_AUDIO = 1 << 2;
+        /// Capturing image previews supported.
+        const GP_OPERATION_CAPTURE_PREVIEW = 1 << 3;
+        /// Camera and Driver configuration supported.
+        const GP_OPERATION_CONFIG = 1 << 4;
+        /// Camera can trigger capture and wait for events.
+        const GP_OPERATION_TRIGGER_CAPTURE = 1 << 5;
+    }
 }
 
-/// A bitmask of image related operations of the device.
-#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
-#[repr(C)]
-pub enum CameraFileOperation {
-    /// No special file operations, just download.
-    GP_FILE_OPERATION_NONE = 0,
-    /// Deletion of files is possible.
-    GP_FILE_OPERATION_DELETE = 1 << 1,
-    /// Previewing viewfinder content is possible.
-    GP_FILE_OPERATION_PREVIEW = 1 << 3,
-    /// Raw retrieval is possible (used by non-JPEG cameras)
-    GP_FILE_OPERATION_RAW = 1 << 4,
-    /// Audio retrieval is possible.
-    GP_FILE_OPERATION_AUDIO = 1 << 5,
-    /// EXIF retrieval is possible.
-    GP_FILE_OPERATION_EXIF = 1 << 6,
+bitflags! {
+    /// A bitmask of image related operations of the device.
+    #[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+    #[repr(transparent)]
+    pub struct CameraFileOperation: c_int {
+        /// No special file operations, just download.
+        const GP_FILE_OPERATION_NONE = 0;
+        /// Deletion of files is possible.
+        const GP_FILE_OPERATION_DELETE = 1 << 1;
+        /// Previewing viewfinder content is possible.
+        const GP_FILE_OPERATION_PREVIEW = 1 << 3;
+        /// Raw retrieval is possible (used by non-JPEG cameras)
+        const GP_FILE_OPERATION_RAW = 1 << 4;
+        /// Audio retrieval is possible.
+        const GP_FILE_OPERATION_AUDIO = 1 << 5;
+        /// EXIF retrieval is possible.
+        const GP_FILE_OPERATION_EXIF = 1 << 6;
+    }
 }
 
-/// A bitmask of filesystem related operations of the device.
-#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
-#[repr(C)]
-pub enum CameraFolderOperation {
-    /// No special filesystem operation.
-    GP_FOLDER_OPERATION_NONE = 0,
-    GP_FOLDER_OPERATION_DELETE_ALL = 1 << 0,
-    /// Upload of files to the device possible.
-    GP_FOLDER_OPERATION_PUT_FILE = 1 << 1,
-    /// Making directories on the device possible.
-    GP_FOLDER_OPERATION_MAKE_DIR = 1 << 2,
-    /// Removing directories from the device possible.
-    GP_FOLDER_OPERATION_REMOVE_DIR = 1 << 3,
+bitflags! {
+    /// A bitmask of filesystem related operations of the device.
+    #[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+    #[repr(transparent)]
+    pub struct CameraFolderOperation: c_int {
+        /// No special filesystem operation.
+        const GP_FOLDER_OPERATION_NONE = 0;
+        const GP_FOLDER_OPERATION_DELETE_ALL = 1 << 0;
+        /// Upload of files to the device possible.
+        const GP_FOLDER_OPERATION_PUT_FILE = 1 << 1;
+        /// Making directories on the device possible.
+        const GP_FOLDER_OPERATION_MAKE_DIR = 1 << 2;
+        /// Removing directories from the device possible.
+        const GP_FOLDER_OPERATION_REMOVE_DIR = 1 << 3;
+    }
 }
 
 /// Describes the properties of a specific camera.
@@ -153,7 +159,7 @@ pub struct CameraAbilitiesList {
     __private: c_void,
 }
 
-extern "C" {
+gp_extern! {
     pub fn gp_abilities_list_new(list: *mut *mut CameraAbilitiesList) -> c_int;
     pub fn gp_abilities_list_free(list: *mut CameraAbilitiesList) -> c_int;
 
@@ -191,7 +197,7 @@ extern "C" {
         abilities: *mut CameraAbilities,
     ) -> c_int;
 
-    pub fn gp_message_codeset(_: *const c_char) -> *const c_char;
+    pub fn gp_message_codeset(codeset: *const c_char) -> *const c_char;
 
     pub fn gp_init_localedir(localedir: *const c_char) -> c_int;
 }
@@ -4,8 +4,9 @@ use crate::{
     abilities_list::CameraAbilities,
     context::GPContext,
     file::{CameraFile, CameraFileType},
-    filesys::{CameraFileInfo, CameraStorageInformation},
+    filesys::{CameraFileInfo, CameraFilesystem, CameraStorageInformation},
     list::CameraList,
+    port::GPPort,
     port_info_list::GPPortInfo,
     widget::CameraWidget,
 };
@@ -18,10 +19,20 @@ use crate::{
 /// The abilities of this type of camera are stored in a CameraAbility
 /// object.
 ///
-/// The details of the Camera object are internal.
+/// The details of the Camera object are internal, only camera drivers
+/// (camlibs) access the fields.
 #[repr(C)]
 pub struct Camera {
-    __private: c_void,
+    /// The port the camera is connected through.
+    pub port: *mut GPPort,
+    /// The filesystem of the camera.
+    pub fs: *mut CameraFilesystem,
+    /// The functions the camera driver implements.
+    pub functions: *mut CameraFunctions,
+    /// Private data of the camera driver.
+    pub pl: *mut CameraPrivateLibrary,
+    /// Private data of libgphoto2.
+    pub pc: *mut CameraPrivateCore,
 }
 
 /// CameraText structure used in various functions.
@@ -81,6 +92,7 @@ pub enum CameraEventType {
     /// last capture is complete
     GP_EVENT_CAPTURE_COMPLETE = 4,
     /// CameraFilePath* = file path on camfs
+    #[cfg(v2_5_27)]
     GP_EVENT_FILE_CHANGED = 5,
 }
 
@@ -135,6 +147,103 @@ pub type CameraGetSingleConfigFunc = extern "C" fn(
     context: *mut GPContext,
 ) -> c_int;
 
+/// Set a configuration tree for the camera and its driver
+///
+/// Counterpart of #CameraGetConfigFunc, applies the changed values of the
+/// widget tree to the driver or the camera.
+pub type CameraSetConfigFunc =
+    extern "C" fn(camera: *mut Camera, widget: *mut CameraWidget, context: *mut GPContext) -> c_int;
+
+/// List the names of all configuration widgets of the camera.
+pub type CameraListConfigFunc =
+    extern "C" fn(camera: *mut Camera, list: *mut CameraList, context: *mut GPContext) -> c_int;
+
+/// Set a single configuration widget by name, see #CameraGetSingleConfigFunc.
+pub type CameraSetSingleConfigFunc = extern "C" fn(
+    camera: *mut Camera,
+    name: *const c_char,
+    widget: *mut CameraWidget,
+    context: *mut GPContext,
+) -> c_int;
+
+/// Called before and after each operation on the camera.
+pub type CameraPrePostFunc = extern "C" fn(camera: *mut Camera, context: *mut GPContext) -> c_int;
+
+/// Capture an image, movie or sound and store its location in `path`.
+pub type CameraCaptureFunc = extern "C" fn(
+    camera: *mut Camera,
+    capture_type: CameraCaptureType,
+    path: *mut CameraFilePath,
+    context: *mut GPContext,
+) -> c_int;
+
+/// Trigger a capture without waiting for the image.
+pub type CameraTriggerCaptureFunc =
+    extern "C" fn(camera: *mut Camera, context: *mut GPContext) -> c_int;
+
+/// Capture a preview image into `file`.
+pub type CameraCapturePreviewFunc =
+    extern "C" fn(camera: *mut Camera, file: *mut CameraFile, context: *mut GPContext) -> c_int;
+
+/// Fill `text` with a summary of the camera.
+pub type CameraSummaryFunc =
+    extern "C" fn(camera: *mut Camera, text: *mut CameraText, context: *mut GPContext) -> c_int;
+
+/// Fill `text` with the manual of the camera driver.
+pub type CameraManualFunc =
+    extern "C" fn(camera: *mut Camera, text: *mut CameraText, context: *mut GPContext) -> c_int;
+
+/// Fill `text` with information about the camera driver.
+pub type CameraAboutFunc =
+    extern "C" fn(camera: *mut Camera, text: *mut CameraText, context: *mut GPContext) -> c_int;
+
+/// Wait up to `timeout` milliseconds for an event from the camera.
+///
+/// The type of `eventdata` depends on `eventtype`, see #CameraEventType.
+pub type CameraWaitForEvent = extern "C" fn(
+    camera: *mut Camera,
+    timeout: c_int,
+    eventtype: *mut CameraEventType,
+    eventdata: *mut *mut c_void,
+    context: *mut GPContext,
+) -> c_int;
+
+/// Functions of a camera driver.
+///
+/// Filled in by the camera driver in its camera_init() function, operations
+/// the driver does not support are left as `None`.
+#[repr(C)]
+pub struct CameraFunctions {
+    /// Called before each operation.
+    pub pre_func: Option<CameraPrePostFunc>,
+    /// Called after each operation.
+    pub post_func: Option<CameraPrePostFunc>,
+    pub exit: Option<CameraExitFunc>,
+
+    // Configuration
+    pub get_config: Option<CameraGetConfigFunc>,
+    pub set_config: Option<CameraSetConfigFunc>,
+    pub list_config: Option<CameraListConfigFunc>,
+    pub get_single_config: Option<CameraGetSingleConfigFunc>,
+    pub set_single_config: Option<CameraSetSingleConfigFunc>,
+
+    // Capturing
+    pub capture: Option<CameraCaptureFunc>,
+    pub trigger_capture: Option<CameraTriggerCaptureFunc>,
+    pub capture_preview: Option<CameraCapturePreviewFunc>,
+
+    // Textual information
+    pub summary: Option<CameraSummaryFunc>,
+    pub manual: Option<CameraManualFunc>,
+    pub about: Option<CameraAboutFunc>,
+
+    // Event interface
+    pub wait_for_event: Option<CameraWaitForEvent>,
+
+    /// Reserved space to use in the future without changing the struct size.
+    pub reserved: [*mut c_void; 8],
+}
+
 // ---- //
 
 pub type CameraTimeoutFunc = extern "C" fn(camera: *mut Camera, context: *mut GPContext) -> c_int;
@@ -158,7 +267,7 @@ pub struct CameraPrivateCore {
     __private: c_void,
 }
 
-extern "C" {
+gp_extern! {
     pub fn gp_camera_new(camera: *mut *mut Camera) -> c_int;
 
     // Preparing initialization//
@@ -316,6 +425,7 @@ extern "C" {
         camera_file: *mut CameraFile,
         context: *mut GPContext,
     ) -> c_int;
+    #[cfg(v2_5_10)]
     pub fn gp_camera_file_read(
         camera: *mut Camera,
         folder: *const c_char,
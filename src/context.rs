@@ -57,7 +57,7 @@ pub type GPContextProgressUpdateFunc =
 pub type GPContextProgressStopFunc =
     extern "C" fn(context: *mut GPContext, id: c_uint, data: *mut c_void);
 
-extern "C" {
+gp_extern! {
     pub fn gp_context_new() -> *mut GPContext;
 
     pub fn gp_context_ref(context: *mut GPContext);
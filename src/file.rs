@@ -1,4 +1,4 @@
-use libc::{c_char, c_int, c_uchar, c_void, time_t};
+use libc::{c_char, c_int, c_uchar, c_ulong, c_void, size_t, time_t};
 
 const_cstr! {
     GP_MIME_TXT         = "text/plain";
@@ -15,7 +15,6 @@ const_cstr! {
     GP_MIME_AVI         = "video/x-msvideo";
     GP_MIME_CRW         = "image/x-canon-raw";
     GP_MIME_CR2         = "image/x-canon-cr2";
-    GP_MIME_CR3         = "image/x-canon-cr3";
     GP_MIME_NEF         = "image/x-nikon-nef";
     GP_MIME_UNKNOWN     = "application/octet-stream";
     GP_MIME_EXIF        = "application/x-exif";
@@ -29,6 +28,11 @@ const_cstr! {
     GP_MIME_ARW         = "image/x-sony-arw";
 }
 
+#[cfg(v2_5_20)]
+const_cstr! {
+    GP_MIME_CR3         = "image/x-canon-cr3";
+}
+
 /// The type of view on the specified file.
 ///
 /// Specifies the file of the current file, usually passed
@@ -71,11 +75,18 @@ pub enum CameraFileAccessType {
     GP_FILE_ACCESSTYPE_HANDLER = 2,
 }
 
+/// Programmatic file handler.
+///
+/// Backs a CameraFile created with gp_file_new_from_handler(), every
+/// function is passed the private pointer given there.
 #[repr(C)]
 pub struct CameraFileHandler {
-    size: extern "C" fn(r#priv: *mut c_void, size: *mut u64) -> c_int,
-    read: extern "C" fn(r#priv: *mut c_void, data: *mut c_uchar, len: *mut u64) -> c_int,
-    write: extern "C" fn(r#priv: *mut c_void, data: *mut c_uchar, len: *mut u64) -> c_int,
+    /// Stores the size of the file in `size`.
+    pub size: extern "C" fn(r#priv: *mut c_void, size: *mut u64) -> c_int,
+    /// Reads up to `len` bytes into `data`, storing the number of bytes read in `len`.
+    pub read: extern "C" fn(r#priv: *mut c_void, data: *mut c_uchar, len: *mut u64) -> c_int,
+    /// Writes `len` bytes from `data`, storing the number of bytes written in `len`.
+    pub write: extern "C" fn(r#priv: *mut c_void, data: *mut c_uchar, len: *mut u64) -> c_int,
 }
 
 ///  File structure.
@@ -87,35 +98,61 @@ pub struct CameraFile {
     __private: c_void,
 }
 
-extern "C" {
+gp_extern! {
     pub fn gp_file_new(file: *mut *mut CameraFile) -> c_int;
     pub fn gp_file_new_from_fd(file: *mut *mut CameraFile, fd: c_int) -> c_int;
-    pub fn gp_file_new_from_handler(file: *mut *mut CameraFile) -> c_int;
-    pub fn gp_file_ref(file: *mut *mut CameraFile) -> c_int;
-    pub fn gp_file_unref(file: *mut *mut CameraFile) -> c_int;
-    pub fn gp_file_free(file: *mut *mut CameraFile) -> c_int;
-
-    pub fn gp_file_set_name(file: *mut *mut CameraFile) -> c_int;
-    pub fn gp_file_get_name(file: *mut *mut CameraFile) -> c_int;
-
-    pub fn gp_file_set_mime_type(file: *mut *mut CameraFile, mime_type: *const c_char) -> c_int;
-    pub fn gp_file_get_mime_type(
+    pub fn gp_file_new_from_handler(
         file: *mut *mut CameraFile,
-        mime_type: *mut *const c_char,
+        handler: *mut CameraFileHandler,
+        r#priv: *mut c_void,
     ) -> c_int;
+    pub fn gp_file_ref(file: *mut CameraFile) -> c_int;
+    pub fn gp_file_unref(file: *mut CameraFile) -> c_int;
+    pub fn gp_file_free(file: *mut CameraFile) -> c_int;
+
+    pub fn gp_file_set_name(file: *mut CameraFile, name: *const c_char) -> c_int;
+    pub fn gp_file_get_name(file: *mut CameraFile, name: *mut *const c_char) -> c_int;
 
-    pub fn gp_file_set_mtime(file: *mut *mut CameraFile, mtime: time_t) -> c_int;
-    pub fn gp_file_get_mtime(file: *mut *mut CameraFile, mtime: *mut time_t) -> c_int;
+    pub fn gp_file_set_mime_type(file: *mut CameraFile, mime_type: *const c_char) -> c_int;
+    pub fn gp_file_get_mime_type(file: *mut CameraFile, mime_type: *mut *const c_char) -> c_int;
 
-    pub fn gp_file_detect_mime_type(file: *mut *mut CameraFile) -> c_int;
-    pub fn gp_file_adjust_name_for_mime_type(file: *mut *mut CameraFile) -> c_int;
+    pub fn gp_file_set_mtime(file: *mut CameraFile, mtime: time_t) -> c_int;
+    pub fn gp_file_get_mtime(file: *mut CameraFile, mtime: *mut time_t) -> c_int;
+
+    pub fn gp_file_detect_mime_type(file: *mut CameraFile) -> c_int;
+    pub fn gp_file_adjust_name_for_mime_type(file: *mut CameraFile) -> c_int;
+    /// The returned name must be freed by the caller.
     pub fn gp_file_get_name_by_type(
-        file: *mut *mut CameraFile,
+        file: *mut CameraFile,
         base_name: *const c_char,
         file_type: CameraFileType,
-        new_name: *mut *const c_char,
+        new_name: *mut *mut c_char,
+    ) -> c_int;
+
+    /// The file takes ownership of `data`, which must be allocated with malloc.
+    pub fn gp_file_set_data_and_size(file: *mut CameraFile, data: *mut c_char, size: c_ulong)
+        -> c_int;
+    /// `data` stays owned by the file.
+    pub fn gp_file_get_data_and_size(
+        file: *mut CameraFile,
+        data: *mut *const c_char,
+        size: *mut c_ulong,
     ) -> c_int;
 
-    pub fn gp_file_set_data_and_size(file: *mut *mut CameraFile) -> c_int;
-    pub fn gp_file_get_data_and_size(file: *mut *mut CameraFile) -> c_int;
+    // Loading and saving //
+
+    pub fn gp_file_open(file: *mut CameraFile, filename: *const c_char) -> c_int;
+    pub fn gp_file_save(file: *mut CameraFile, filename: *const c_char) -> c_int;
+    pub fn gp_file_clean(file: *mut CameraFile) -> c_int;
+    pub fn gp_file_copy(destination: *mut CameraFile, source: *mut CameraFile) -> c_int;
+
+    // For use by camera drivers //
+
+    pub fn gp_file_append(file: *mut CameraFile, data: *const c_char, size: c_ulong) -> c_int;
+    pub fn gp_file_slurp(
+        file: *mut CameraFile,
+        data: *mut c_char,
+        size: size_t,
+        readlen: *mut size_t,
+    ) -> c_int;
 }
@@ -1,51 +1,61 @@
 use libc::{c_char, c_int, c_void, time_t};
 
-/// Bitmask on what fields are set in the CameraFileInfo structure.
-///
-/// Bitmask to mark up which fields are set in the CameraFileInfo
-/// structure. The other fields might be uninitialized.
-/// If you set information via gp_camera_file_set_info() you
-/// need to set those flags. If you retrieve information via
-/// gp_camera_file_get_info() you need to check those flags.
-/// They are separate for both "normal" and "preview" parts
-/// and are mostly image related.
-#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
-#[repr(C)]
-pub enum CameraFileInfoFields {
-    /// No fields set.
-    GP_FILE_INFO_NONE = 0,
-    /// he MIME type is set.
-    GP_FILE_INFO_TYPE = 1 << 0,
-    // The filesize is set.
-    GP_FILE_INFO_SIZE = 1 << 2,
-    /// The width is set.
-    GP_FILE_INFO_WIDTH = 1 << 3,
-    /// The height is set.
-    GP_FILE_INFO_HEIGHT = 1 << 4,
-    /// The access permissions are set.
-    GP_FILE_INFO_PERMISSIONS = 1 << 5,
-    /// The status is set (downloaded).
-    GP_FILE_INFO_STATUS = 1 << 6,
-    /// The modification time is set.
-    GP_FILE_INFO_MTIME = 1 << 7,
-    /// All possible fields set. Internal.
-    GP_FILE_INFO_ALL = 0xFF,
+use crate::{
+    context::GPContext,
+    file::{CameraFile, CameraFileType},
+    list::CameraList,
+};
+
+bitflags! {
+    /// Bitmask on what fields are set in the CameraFileInfo structure.
+    ///
+    /// Bitmask to mark up which fields are set in the CameraFileInfo
+    /// structure. The other fields might be uninitialized.
+    /// If you set information via gp_camera_file_set_info() you
+    /// need to set those flags. If you retrieve information via
+    /// gp_camera_file_get_info() you need to check those flags.
+    /// They are separate for both "normal" and "preview" parts
+    /// and are mostly image related.
+    #[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+    #[repr(transparent)]
+    pub struct CameraFileInfoFields: c_int {
+        /// No fields set.
+        const GP_FILE_INFO_NONE = 0;
+        /// he MIME type is set.
+        const GP_FILE_INFO_TYPE = 1 << 0;
+        // The filesize is set.
+        const GP_FILE_INFO_SIZE = 1 << 2;
+        /// The width is set.
+        const GP_FILE_INFO_WIDTH = 1 << 3;
+        /// The height is set.
+        const GP_FILE_INFO_HEIGHT = 1 << 4;
+        /// The access permissions are set.
+        const GP_FILE_INFO_PERMISSIONS = 1 << 5;
+        /// The status is set (downloaded).
+        const GP_FILE_INFO_STATUS = 1 << 6;
+        /// The modification time is set.
+        const GP_FILE_INFO_MTIME = 1 << 7;
+        /// All possible fields set. Internal.
+        const GP_FILE_INFO_ALL = 0xFF;
+    }
 }
 
-/// Bitmask containing the file permission flags.
-///
-/// Possible flag values of the permission entry in the file information.
-#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
-#[repr(C)]
-pub enum CameraFilePermissions {
-    /// No permissions.
-    GP_FILE_PERM_NONE = 0,
-    /// Read permissions.
-    GP_FILE_PERM_READ = 1 << 0,
-    /// Write permissions
-    GP_FILE_PERM_DELETE = 1 << 1,
-    /// Internal.
-    GP_FILE_PERM_ALL = 0xFF,
+bitflags! {
+    /// Bitmask containing the file permission flags.
+    ///
+    /// Possible flag values of the permission entry in the file information.
+    #[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+    #[repr(transparent)]
+    pub struct CameraFilePermissions: c_int {
+        /// No permissions.
+        const GP_FILE_PERM_NONE = 0;
+        /// Read permissions.
+        const GP_FILE_PERM_READ = 1 << 0;
+        /// Write permissions
+        const GP_FILE_PERM_DELETE = 1 << 1;
+        /// Internal.
+        const GP_FILE_PERM_ALL = 0xFF;
+    }
 }
 
 /// Possible status values.
@@ -131,35 +141,37 @@ pub struct CameraFileInfo {
     pub audio: CameraFileInfoAudio,
 }
 
-/// Storage information flags.
-///
-/// Bitmask to specify which entries of the filesystem
-/// storage information is set.
-#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
-#[repr(C)]
-pub enum CameraStorageInfoFields {
-    /// The base directory.
+bitflags! {
+    /// Storage information flags.
     ///
-    ///  Usually / if just 1 storage is attached.
-    GP_STORAGEINFO_BASE = (1 << 0),
-    /// Label of the filesystem.
-    ///
-    /// Could also be a DOS label.
-    GP_STORAGEINFO_LABEL = (1 << 1),
-    /// More verbose description.
-    GP_STORAGEINFO_DESCRIPTION = (1 << 2),
-    /// Access permissions.
-    GP_STORAGEINFO_ACCESS = (1 << 3),
-    /// Hardware type.
-    GP_STORAGEINFO_STORAGETYPE = (1 << 4),
-    /// Filesystem type.
-    GP_STORAGEINFO_FILESYSTEMTYPE = (1 << 5),
-    /// Maximum capacity in kbytes
-    GP_STORAGEINFO_MAXCAPACITY = (1 << 6),
-    /// Free space in kbytes.
-    GP_STORAGEINFO_FREESPACEKBYTES = (1 << 7),
-    /// Free space in images.
-    GP_STORAGEINFO_FREESPACEIMAGES = (1 << 8),
+    /// Bitmask to specify which entries of the filesystem
+    /// storage information is set.
+    #[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+    #[repr(transparent)]
+    pub struct CameraStorageInfoFields: c_int {
+        /// The base directory.
+        ///
+        ///  Usually / if just 1 storage is attached.
+        const GP_STORAGEINFO_BASE = (1 << 0);
+        /// Label of the filesystem.
+        ///
+        /// Could also be a DOS label.
+        const GP_STORAGEINFO_LABEL = (1 << 1);
+        /// More verbose description.
+        const GP_STORAGEINFO_DESCRIPTION = (1 << 2);
+        /// Access permissions.
+        const GP_STORAGEINFO_ACCESS = (1 << 3);
+        /// Hardware type.
+        const GP_STORAGEINFO_STORAGETYPE = (1 << 4);
+        /// Filesystem type.
+        const GP_STORAGEINFO_FILESYSTEMTYPE = (1 << 5);
+        /// Maximum capacity in kbytes
+        const GP_STORAGEINFO_MAXCAPACITY = (1 << 6);
+        /// Free space in kbytes.
+        const GP_STORAGEINFO_FREESPACEKBYTES = (1 << 7);
+        /// Free space in images.
+        const GP_STORAGEINFO_FREESPACEIMAGES = (1 << 8);
+    }
 }
 
 /// Hardware storage types.
@@ -251,9 +263,303 @@ pub struct CameraFilesystem {
     __private: c_void,
 }
 
-extern "C" {
+/// List files or folders of `folder` into `list`.
+pub type CameraFilesystemListFunc = extern "C" fn(
+    fs: *mut CameraFilesystem,
+    folder: *const c_char,
+    list: *mut CameraList,
+    data: *mut c_void,
+    context: *mut GPContext,
+) -> c_int;
+
+/// Set the information of a file.
+pub type CameraFilesystemSetInfoFunc = extern "C" fn(
+    fs: *mut CameraFilesystem,
+    folder: *const c_char,
+    filename: *const c_char,
+    info: CameraFileInfo,
+    data: *mut c_void,
+    context: *mut GPContext,
+) -> c_int;
+
+/// Get the information of a file.
+pub type CameraFilesystemGetInfoFunc = extern "C" fn(
+    fs: *mut CameraFilesystem,
+    folder: *const c_char,
+    filename: *const c_char,
+    info: *mut CameraFileInfo,
+    data: *mut c_void,
+    context: *mut GPContext,
+) -> c_int;
+
+/// Download a view of a file into `file`.
+pub type CameraFilesystemGetFileFunc = extern "C" fn(
+    fs: *mut CameraFilesystem,
+    folder: *const c_char,
+    filename: *const c_char,
+    file_type: CameraFileType,
+    file: *mut CameraFile,
+    data: *mut c_void,
+    context: *mut GPContext,
+) -> c_int;
+
+/// Read `size` bytes of a view of a file starting at `offset` into `buf`.
+///
+/// `size` is updated to the number of bytes read.
+#[cfg(v2_5_10)]
+pub type CameraFilesystemReadFileFunc = extern "C" fn(
+    fs: *mut CameraFilesystem,
+    folder: *const c_char,
+    filename: *const c_char,
+    file_type: CameraFileType,
+    offset: u64,
+    buf: *mut c_char,
+    size: *mut u64,
+    data: *mut c_void,
+    context: *mut GPContext,
+) -> c_int;
+
+/// Delete a file.
+pub type CameraFilesystemDeleteFileFunc = extern "C" fn(
+    fs: *mut CameraFilesystem,
+    folder: *const c_char,
+    filename: *const c_char,
+    data: *mut c_void,
+    context: *mut GPContext,
+) -> c_int;
+
+/// Upload `file` into `folder`.
+pub type CameraFilesystemPutFileFunc = extern "C" fn(
+    fs: *mut CameraFilesystem,
+    folder: *const c_char,
+    filename: *const c_char,
+    file_type: CameraFileType,
+    file: *mut CameraFile,
+    data: *mut c_void,
+    context: *mut GPContext,
+) -> c_int;
+
+/// Delete all files of `folder`.
+pub type CameraFilesystemDeleteAllFunc = extern "C" fn(
+    fs: *mut CameraFilesystem,
+    folder: *const c_char,
+    data: *mut c_void,
+    context: *mut GPContext,
+) -> c_int;
+
+/// Create or remove the directory `name` in `folder`.
+pub type CameraFilesystemDirFunc = extern "C" fn(
+    fs: *mut CameraFilesystem,
+    folder: *const c_char,
+    name: *const c_char,
+    data: *mut c_void,
+    context: *mut GPContext,
+) -> c_int;
+
+/// Get the storage information of the device.
+///
+/// The array stored in `sinfos` is allocated with malloc and freed by the caller.
+pub type CameraFilesystemStorageInfoFunc = extern "C" fn(
+    fs: *mut CameraFilesystem,
+    sinfos: *mut *mut CameraStorageInformation,
+    nrofsinfos: *mut c_int,
+    data: *mut c_void,
+    context: *mut GPContext,
+) -> c_int;
+
+/// Filesystem operations of a camera driver.
+///
+/// Passed to gp_filesystem_set_funcs(), operations the driver does not
+/// support are left as `None`.
+#[repr(C)]
+pub struct CameraFilesystemFuncs {
+    pub file_list_func: Option<CameraFilesystemListFunc>,
+    pub folder_list_func: Option<CameraFilesystemListFunc>,
+    pub put_file_func: Option<CameraFilesystemPutFileFunc>,
+    pub delete_all_func: Option<CameraFilesystemDeleteAllFunc>,
+    pub get_info_func: Option<CameraFilesystemGetInfoFunc>,
+    pub set_info_func: Option<CameraFilesystemSetInfoFunc>,
+    pub make_dir_func: Option<CameraFilesystemDirFunc>,
+    pub remove_dir_func: Option<CameraFilesystemDirFunc>,
+    pub get_file_func: Option<CameraFilesystemGetFileFunc>,
+    #[cfg(v2_5_10)]
+    pub read_file_func: Option<CameraFilesystemReadFileFunc>,
+    pub del_file_func: Option<CameraFilesystemDeleteFileFunc>,
+    pub storage_info_func: Option<CameraFilesystemStorageInfoFunc>,
+
+    /// For later use.
+    #[cfg(v2_5_10)]
+    pub unused: [*mut c_void; 31],
+    /// For later use.
+    #[cfg(not(v2_5_10))]
+    pub unused: [*mut c_void; 32],
+}
+
+gp_extern! {
     pub fn gp_filesystem_new(fs: *mut *mut CameraFilesystem) -> c_int;
     pub fn gp_filesystem_free(fs: *mut CameraFilesystem) -> c_int;
 
-    // TODO
+    // Manual editing //
+
+    pub fn gp_filesystem_append(
+        fs: *mut CameraFilesystem,
+        folder: *const c_char,
+        filename: *const c_char,
+        context: *mut GPContext,
+    ) -> c_int;
+    pub fn gp_filesystem_set_info_noop(
+        fs: *mut CameraFilesystem,
+        folder: *const c_char,
+        filename: *const c_char,
+        info: CameraFileInfo,
+        context: *mut GPContext,
+    ) -> c_int;
+    pub fn gp_filesystem_set_file_noop(
+        fs: *mut CameraFilesystem,
+        folder: *const c_char,
+        filename: *const c_char,
+        file_type: CameraFileType,
+        file: *mut CameraFile,
+        context: *mut GPContext,
+    ) -> c_int;
+    pub fn gp_filesystem_delete_file_noop(
+        fs: *mut CameraFilesystem,
+        folder: *const c_char,
+        filename: *const c_char,
+        context: *mut GPContext,
+    ) -> c_int;
+    pub fn gp_filesystem_reset(fs: *mut CameraFilesystem) -> c_int;
+
+    // Information retrieval //
+
+    pub fn gp_filesystem_count(
+        fs: *mut CameraFilesystem,
+        folder: *const c_char,
+        context: *mut GPContext,
+    ) -> c_int;
+    pub fn gp_filesystem_name(
+        fs: *mut CameraFilesystem,
+        folder: *const c_char,
+        filenumber: c_int,
+        filename: *mut *const c_char,
+        context: *mut GPContext,
+    ) -> c_int;
+    /// The returned folder must be freed by the caller.
+    pub fn gp_filesystem_get_folder(
+        fs: *mut CameraFilesystem,
+        filename: *const c_char,
+        folder: *mut *mut c_char,
+        context: *mut GPContext,
+    ) -> c_int;
+    pub fn gp_filesystem_number(
+        fs: *mut CameraFilesystem,
+        folder: *const c_char,
+        filename: *const c_char,
+        context: *mut GPContext,
+    ) -> c_int;
+
+    // Listing //
+
+    pub fn gp_filesystem_list_files(
+        fs: *mut CameraFilesystem,
+        folder: *const c_char,
+        list: *mut CameraList,
+        context: *mut GPContext,
+    ) -> c_int;
+    pub fn gp_filesystem_list_folders(
+        fs: *mut CameraFilesystem,
+        folder: *const c_char,
+        list: *mut CameraList,
+        context: *mut GPContext,
+    ) -> c_int;
+
+    // File information //
+
+    pub fn gp_filesystem_get_info(
+        fs: *mut CameraFilesystem,
+        folder: *const c_char,
+        filename: *const c_char,
+        info: *mut CameraFileInfo,
+        context: *mut GPContext,
+    ) -> c_int;
+    pub fn gp_filesystem_set_info(
+        fs: *mut CameraFilesystem,
+        folder: *const c_char,
+        filename: *const c_char,
+        info: CameraFileInfo,
+        context: *mut GPContext,
+    ) -> c_int;
+
+    // Files //
+
+    pub fn gp_filesystem_get_file(
+        fs: *mut CameraFilesystem,
+        folder: *const c_char,
+        filename: *const c_char,
+        file_type: CameraFileType,
+        file: *mut CameraFile,
+        context: *mut GPContext,
+    ) -> c_int;
+    #[cfg(v2_5_10)]
+    pub fn gp_filesystem_read_file(
+        fs: *mut CameraFilesystem,
+        folder: *const c_char,
+        filename: *const c_char,
+        file_type: CameraFileType,
+        offset: u64,
+        buf: *mut c_char,
+        size: *mut u64,
+        context: *mut GPContext,
+    ) -> c_int;
+    pub fn gp_filesystem_delete_file(
+        fs: *mut CameraFilesystem,
+        folder: *const c_char,
+        filename: *const c_char,
+        context: *mut GPContext,
+    ) -> c_int;
+
+    // Folders //
+
+    pub fn gp_filesystem_set_funcs(
+        fs: *mut CameraFilesystem,
+        funcs: *mut CameraFilesystemFuncs,
+        data: *mut c_void,
+    ) -> c_int;
+    pub fn gp_filesystem_put_file(
+        fs: *mut CameraFilesystem,
+        folder: *const c_char,
+        filename: *const c_char,
+        file_type: CameraFileType,
+        file: *mut CameraFile,
+        context: *mut GPContext,
+    ) -> c_int;
+    pub fn gp_filesystem_delete_all(
+        fs: *mut CameraFilesystem,
+        folder: *const c_char,
+        context: *mut GPContext,
+    ) -> c_int;
+    pub fn gp_filesystem_make_dir(
+        fs: *mut CameraFilesystem,
+        folder: *const c_char,
+        name: *const c_char,
+        context: *mut GPContext,
+    ) -> c_int;
+    pub fn gp_filesystem_remove_dir(
+        fs: *mut CameraFilesystem,
+        folder: *const c_char,
+        name: *const c_char,
+        context: *mut GPContext,
+    ) -> c_int;
+
+    /// The returned array must be freed by the caller.
+    pub fn gp_filesystem_get_storageinfo(
+        fs: *mut CameraFilesystem,
+        sinfos: *mut *mut CameraStorageInformation,
+        nrofsinfos: *mut c_int,
+        context: *mut GPContext,
+    ) -> c_int;
+
+    // For debugging //
+
+    pub fn gp_filesystem_dump(fs: *mut CameraFilesystem) -> c_int;
 }
@@ -1,7 +1,15 @@
 #![allow(non_camel_case_types)]
 #[macro_use]
+extern crate bitflags;
+#[macro_use]
 extern crate const_cstr;
 
+#[macro_use]
+mod macros;
+
+#[cfg(all(feature = "vendored", feature = "runtime-load"))]
+compile_error!("the `vendored` and `runtime-load` features are mutually exclusive");
+
 pub mod abilities_list;
 pub mod camera;
 pub mod context;
@@ -9,13 +17,22 @@ pub mod file;
 pub mod filesys;
 pub mod library;
 pub mod list;
+#[cfg(feature = "runtime-load")]
+pub mod loader;
 pub mod port;
 pub mod port_info_list;
+pub mod port_library;
+pub mod port_log;
 pub mod result;
 pub mod setting;
+#[cfg(feature = "vendored")]
+pub mod vendored;
 pub mod version;
 pub mod widget;
 
+#[cfg(feature = "runtime-load")]
+pub use loader::{is_loaded, load, load_from, LoadError};
+
 #[cfg(test)]
 mod tests {
     #[test]
@@ -25,7 +42,11 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::bool_assert_comparison)]
     fn test_context() {
+        #[cfg(feature = "runtime-load")]
+        crate::load().unwrap();
+
         let ptr = unsafe { crate::context::gp_context_new() };
 
         assert_eq!(ptr.is_null(), false);
@@ -34,4 +55,88 @@ mod tests {
             crate::context::gp_context_unref(ptr);
         }
     }
+
+    #[test]
+    fn test_log() {
+        use std::{ffi::CStr, ptr, sync::Mutex};
+
+        use libc::{c_char, c_void};
+
+        use crate::port_log::{GPLogLevel, GP_LOG_ALL};
+
+        extern "C" fn log_func(
+            _level: GPLogLevel,
+            domain: *const c_char,
+            message: *const c_char,
+            data: *mut c_void,
+        ) {
+            let messages = unsafe { &*(data as *const Mutex<Vec<String>>) };
+            let (domain, message) = unsafe { (CStr::from_ptr(domain), CStr::from_ptr(message)) };
+
+            messages.lock().unwrap().push(format!(
+                "{}: {}",
+                domain.to_string_lossy(),
+                message.to_string_lossy()
+            ));
+        }
+
+        #[cfg(feature = "runtime-load")]
+        crate::load().unwrap();
+
+        let messages = Mutex::new(Vec::<String>::new());
+
+        unsafe {
+            let id = crate::port_log::gp_log_add_func(
+                GP_LOG_ALL,
+                log_func,
+                &messages as *const _ as *mut c_void,
+            );
+            assert!(id >= 0);
+
+            let context = crate::context::gp_context_new();
+            let mut list = ptr::null_mut();
+            crate::abilities_list::gp_abilities_list_new(&mut list);
+            crate::abilities_list::gp_abilities_list_load(list, context);
+
+            crate::port_log::gp_log_remove_func(id);
+            crate::abilities_list::gp_abilities_list_free(list);
+            crate::context::gp_context_unref(context);
+        }
+
+        let messages = messages.into_inner().unwrap();
+        assert!(
+            messages
+                .iter()
+                .any(|message| message.starts_with("gphoto2-abilities-list: ")),
+            "no messages from gp_abilities_list_load: {:?}",
+            messages
+        );
+    }
+
+    #[test]
+    fn test_combined_flags() {
+        use crate::abilities_list::CameraOperation;
+
+        // A camera supporting capture and configuration
+        let operations = CameraOperation::from_bits_retain(0x11);
+
+        assert!(operations.contains(CameraOperation::GP_OPERATION_CAPTURE_IMAGE));
+        assert!(operations.contains(CameraOperation::GP_OPERATION_CONFIG));
+        assert!(!operations.contains(CameraOperation::GP_OPERATION_TRIGGER_CAPTURE));
+        assert_eq!(operations.iter().count(), 2);
+        assert_eq!(
+            format!("{:?}", operations),
+            "CameraOperation(GP_OPERATION_CAPTURE_IMAGE | GP_OPERATION_CONFIG)"
+        );
+    }
+
+    #[cfg(feature = "runtime-load")]
+    #[test]
+    fn test_load_missing_library() {
+        // `load_from` does nothing once another test has loaded the library
+        let err = crate::loader::open_path("/nonexistent/libgphoto2.so".as_ref()).unwrap_err();
+
+        assert!(matches!(err, crate::LoadError::Library { .. }));
+        assert!(err.to_string().contains("/nonexistent/libgphoto2.so"));
+    }
 }
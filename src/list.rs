@@ -10,7 +10,7 @@ pub struct CameraList {
     __private: c_void,
 }
 
-extern "C" {
+gp_extern! {
     pub fn gp_list_new(list: *mut *mut CameraList) -> c_int;
     pub fn gp_list_ref(list: *mut CameraList) -> c_int;
     pub fn gp_list_unref(list: *mut CameraList) -> c_int;
@@ -0,0 +1,203 @@
+//! Loading libgphoto2 at runtime (`runtime-load` feature).
+//!
+//! Instead of linking against libgphoto2, every function is looked up in the
+//! shared library when [`load`] is called. Calling any binding before a
+//! successful [`load`] panics.
+//!
+//! The bindings of releases after 2.5.0 are only declared when their `v2_5_*`
+//! feature is enabled, not from the version found at build time, so [`load`]
+//! works with any libgphoto2 that has the enabled bindings.
+
+use std::{
+    error::Error,
+    ffi::OsStr,
+    fmt,
+    sync::{Mutex, OnceLock},
+};
+
+use libloading::Library;
+
+/// The libraries the function tables point into, kept open forever once
+/// every function has been resolved.
+static LIBRARIES: OnceLock<Vec<Library>> = OnceLock::new();
+
+/// Serializes concurrent calls to [`load`].
+static LOADING: Mutex<()> = Mutex::new(());
+
+#[cfg(target_os = "linux")]
+const LIBGPHOTO2: &[&str] = &["libgphoto2.so.6", "libgphoto2.so"];
+#[cfg(target_os = "linux")]
+const LIBGPHOTO2_PORT: &[&str] = &["libgphoto2_port.so.12", "libgphoto2_port.so"];
+
+#[cfg(target_os = "macos")]
+const LIBGPHOTO2: &[&str] = &["libgphoto2.6.dylib", "libgphoto2.dylib"];
+#[cfg(target_os = "macos")]
+const LIBGPHOTO2_PORT: &[&str] = &["libgphoto2_port.12.dylib", "libgphoto2_port.dylib"];
+
+#[cfg(windows)]
+const LIBGPHOTO2: &[&str] = &["libgphoto2-6.dll", "libgphoto2.dll"];
+#[cfg(windows)]
+const LIBGPHOTO2_PORT: &[&str] = &["libgphoto2_port-12.dll", "libgphoto2_port.dll"];
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+const LIBGPHOTO2: &[&str] = &["libgphoto2.so"];
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+const LIBGPHOTO2_PORT: &[&str] = &["libgphoto2_port.so"];
+
+/// Error returned when libgphoto2 could not be loaded.
+#[derive(Debug)]
+pub enum LoadError {
+    /// The shared library could not be opened.
+    Library {
+        /// The file names that were tried.
+        names: Vec<String>,
+        /// The error of the last attempt.
+        source: libloading::Error,
+    },
+    /// The library was opened but does not export a function.
+    Symbol {
+        /// Name of the missing function.
+        name: &'static str,
+        /// The error of the last library it was looked up in.
+        source: libloading::Error,
+    },
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoadError::Library { names, source } => {
+                write!(f, "could not load {}: {}", names.join(" or "), source)
+            }
+            LoadError::Symbol { name, source } => {
+                write!(f, "libgphoto2 does not export `{}`: {}", name, source)
+            }
+        }
+    }
+}
+
+impl Error for LoadError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            LoadError::Library { source, .. } | LoadError::Symbol { source, .. } => Some(source),
+        }
+    }
+}
+
+/// Loads libgphoto2 and libgphoto2_port from the default library search path
+/// and resolves every binding.
+///
+/// Loading again after a successful load does nothing.
+pub fn load() -> Result<(), LoadError> {
+    load_libraries(|| Ok(vec![open(LIBGPHOTO2)?, open(LIBGPHOTO2_PORT)?]))
+}
+
+/// Like [`load`], but opens libgphoto2 and libgphoto2_port from the given paths.
+pub fn load_from(
+    libgphoto2: impl AsRef<OsStr>,
+    libgphoto2_port: impl AsRef<OsStr>,
+) -> Result<(), LoadError> {
+    load_libraries(|| {
+        Ok(vec![
+            open_path(libgphoto2.as_ref())?,
+            open_path(libgphoto2_port.as_ref())?,
+        ])
+    })
+}
+
+/// Returns whether [`load`] has succeeded.
+pub fn is_loaded() -> bool {
+    LIBRARIES.get().is_some()
+}
+
+/// Resolves the function tables of every module, installing them only if
+/// all of them could be resolved.
+macro_rules! load_modules {
+    ($libraries:expr, $($module:ident),* $(,)?) => {{
+        $(let $module = crate::$module::resolve_functions(&$libraries)?;)*
+
+        $(crate::$module::install_functions($module);)*
+        LIBRARIES.get_or_init(|| $libraries);
+    }};
+}
+
+fn load_libraries(open: impl FnOnce() -> Result<Vec<Library>, LoadError>) -> Result<(), LoadError> {
+    let _guard = LOADING.lock().unwrap_or_else(|err| err.into_inner());
+
+    if is_loaded() {
+        return Ok(());
+    }
+
+    let libraries = open()?;
+
+    load_modules!(
+        libraries,
+        abilities_list,
+        camera,
+        context,
+        file,
+        filesys,
+        list,
+        port,
+        port_info_list,
+        port_log,
+        result,
+        setting,
+        version,
+        widget,
+    );
+
+    Ok(())
+}
+
+fn open(names: &[&str]) -> Result<Library, LoadError> {
+    let mut last_error = None;
+
+    for name in names {
+        match unsafe { Library::new(name) } {
+            Ok(library) => return Ok(library),
+            Err(err) => last_error = Some(err),
+        }
+    }
+
+    Err(LoadError::Library {
+        names: names.iter().map(|name| name.to_string()).collect(),
+        source: last_error.expect("no library names given"),
+    })
+}
+
+pub(crate) fn open_path(path: &OsStr) -> Result<Library, LoadError> {
+    unsafe { Library::new(path) }.map_err(|source| LoadError::Library {
+        names: vec![path.to_string_lossy().into_owned()],
+        source,
+    })
+}
+
+/// Looks up `name` in `libraries`.
+///
+/// # Safety
+///
+/// `T` must be the function pointer type matching the C declaration of `name`,
+/// and must not be called after `libraries` are closed.
+pub(crate) unsafe fn symbol<T: Copy>(
+    libraries: &[Library],
+    name: &'static str,
+) -> Result<T, LoadError> {
+    let mut symbol_name = Vec::with_capacity(name.len() + 1);
+    symbol_name.extend_from_slice(name.as_bytes());
+    symbol_name.push(0);
+
+    let mut last_error = None;
+
+    for library in libraries {
+        match library.get::<T>(&symbol_name) {
+            Ok(symbol) => return Ok(*symbol),
+            Err(err) => last_error = Some(err),
+        }
+    }
+
+    Err(LoadError::Symbol {
+        name,
+        source: last_error.expect("at least one library is loaded"),
+    })
+}
@@ -0,0 +1,87 @@
+/// Declares the libgphoto2 functions of a module.
+///
+/// By default this is a plain `extern "C"` block resolved by the linker.
+/// With the `runtime-load` feature every function is instead looked up in
+/// the library opened by [`crate::load`] and stored in the module's
+/// `Functions` table, and a wrapper with the same signature forwards to it.
+///
+/// Variadic functions cannot be wrapped on stable Rust, with `runtime-load`
+/// they are only reachable through `functions()`.
+///
+/// Every module may only use this macro once.
+#[cfg(not(feature = "runtime-load"))]
+macro_rules! gp_extern {
+    ($(
+        $(#[$meta:meta])*
+        pub fn $name:ident($($params:tt)*) $(-> $ret:ty)?;
+    )*) => {
+        extern "C" {
+            $(
+                $(#[$meta])*
+                pub fn $name($($params)*) $(-> $ret)?;
+            )*
+        }
+    };
+}
+
+#[cfg(feature = "runtime-load")]
+macro_rules! gp_extern {
+    ($(
+        $(#[$meta:meta])*
+        pub fn $name:ident($($params:tt)*) $(-> $ret:ty)?;
+    )*) => {
+        static FUNCTIONS: ::std::sync::OnceLock<Functions> = ::std::sync::OnceLock::new();
+
+        /// The functions of this module, resolved from the loaded library.
+        #[allow(non_snake_case)]
+        pub struct Functions {
+            $(
+                $(#[$meta])*
+                pub $name: unsafe extern "C" fn($($params)*) $(-> $ret)?,
+            )*
+        }
+
+        /// Returns the function table of this module.
+        ///
+        /// # Panics
+        ///
+        /// Panics if [`crate::load`] has not succeeded yet.
+        pub fn functions() -> &'static Functions {
+            FUNCTIONS
+                .get()
+                .expect("libgphoto2 has not been loaded, call libgphoto2_sys::load() first")
+        }
+
+        // Only `cfg`s matter on the fields, doc comments are forwarded as well
+        #[allow(unused_doc_comments)]
+        pub(crate) fn resolve_functions(
+            libraries: &[::libloading::Library],
+        ) -> Result<Functions, crate::loader::LoadError> {
+            Ok(Functions {
+                $(
+                    $(#[$meta])*
+                    $name: unsafe { crate::loader::symbol(libraries, stringify!($name))? },
+                )*
+            })
+        }
+
+        pub(crate) fn install_functions(functions: Functions) {
+            let _ = FUNCTIONS.set(functions);
+        }
+
+        $(
+            gp_extern!(@wrapper $(#[$meta])* $name($($params)*) $(-> $ret)?);
+        )*
+    };
+
+    (@wrapper $(#[$meta:meta])* $name:ident($($arg:ident: $ty:ty,)+ ...) $(-> $ret:ty)?) => {};
+
+    (@wrapper $(#[$meta:meta])* $name:ident($($arg:ident: $ty:ty),* $(,)?) $(-> $ret:ty)?) => {
+        $(#[$meta])*
+        #[inline]
+        #[allow(clippy::missing_safety_doc, clippy::too_many_arguments)]
+        pub unsafe fn $name($($arg: $ty),*) $(-> $ret)? {
+            (functions().$name)($($arg),*)
+        }
+    };
+}
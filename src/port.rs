@@ -1,4 +1,6 @@
-use libc::{c_char, c_int};
+use libc::{c_char, c_int, c_void};
+
+use crate::port_info_list::{GPPortInfo, GPPortType};
 
 /// Serial parity
 ///
@@ -21,41 +23,258 @@ pub const GP_PORT_MAX_BUF_LEN: c_int = 4096;
 #[repr(C)]
 pub struct GPPortSettingsSerial {
     /// The portname (/dev/ttyX)
-    port: [c_char; 128],
+    pub port: [c_char; 128],
 
     /// The baudrate of the device.
-    speed: c_int,
+    pub speed: c_int,
+
+    /// How many bits are used.
+    pub bits: c_int,
 
     /// Parity data
-    bits: GPPortSerialParity,
+    pub parity: GPPortSerialParity,
 
     /// How many stop bits are used.
-    stopbits: c_int,
+    pub stopbits: c_int,
 }
 
 /// Port settings for USB ports.
+#[repr(C)]
 pub struct GPPortSettingsUSB {
     /// Bulk IN endpoint used.
-    inep: c_int,
+    pub inep: c_int,
 
     /// Bulk OUT endpoint used.
-    outep: c_int,
+    pub outep: c_int,
 
     /// Interrupt endpoint used.
-    intep: c_int,
+    pub intep: c_int,
 
     /// USB bConfigurationValue used.
-    config: c_int,
+    pub config: c_int,
 
     /// USB Interface number used.
-    interface: c_int,
+    pub interface: c_int,
 
     /// USB Alternative Setting used.
-    altsetting: c_int,
+    pub altsetting: c_int,
 
     ///  Maximum USB packetsize of the IN endpoint.
-    maxpacketsize: c_int,
+    pub maxpacketsize: c_int,
 
     /// USB Portname. Specific to lowlevel USB.
-    port: [c_char; 64],
+    pub port: [c_char; 64],
+}
+
+/// Port settings for direct IO to USB mass storage devices.
+#[repr(C)]
+pub struct GPPortSettingsUsbDiskDirect {
+    /// The ports device node path (/dev/sdX)
+    pub path: [c_char; 128],
+}
+
+/// Port settings for USB mass storage raw SCSI ports.
+#[repr(C)]
+pub struct GPPortSettingsUsbScsi {
+    /// The ports device node path (/dev/sgX)
+    pub path: [c_char; 128],
+}
+
+/// Union of port settings.
+///
+/// This contains a shared union of possible settings for ports needing
+/// them. Which member is valid depends on the type of the port.
+///
+/// The fields of the members are public so that settings read with
+/// gp_port_get_settings() can be changed and passed to
+/// gp_port_set_settings().
+#[repr(C)]
+pub union GPPortSettings {
+    /// Serial specific settings
+    pub serial: std::mem::ManuallyDrop<GPPortSettingsSerial>,
+    /// USB specific settings
+    pub usb: std::mem::ManuallyDrop<GPPortSettingsUSB>,
+    /// usb disk direct port specific settings
+    pub usbdiskdirect: std::mem::ManuallyDrop<GPPortSettingsUsbDiskDirect>,
+    /// usb scsi port specific settings
+    pub usbscsi: std::mem::ManuallyDrop<GPPortSettingsUsbScsi>,
+}
+
+/// USB bulk IN endpoint, for gp_port_usb_clear_halt().
+pub const GP_PORT_USB_ENDPOINT_IN: c_int = 0;
+/// USB bulk OUT endpoint, for gp_port_usb_clear_halt().
+pub const GP_PORT_USB_ENDPOINT_OUT: c_int = 1;
+/// USB interrupt endpoint, for gp_port_usb_clear_halt().
+pub const GP_PORT_USB_ENDPOINT_INT: c_int = 2;
+
+#[repr(C)]
+pub struct GPPortPrivateLibrary {
+    __private: c_void,
+}
+
+#[repr(C)]
+pub struct GPPortPrivateCore {
+    __private: c_void,
+}
+
+/// The port structure.
+///
+/// Port structure. While the internals are exposed, please do not
+/// access them directly, use the accessor functions.
+#[repr(C)]
+pub struct GPPort {
+    /// Actual type of this port
+    pub r#type: GPPortType,
+    /// Current port settings.
+    pub settings: GPPortSettings,
+    /// Settings to be committed.
+    pub settings_pending: GPPortSettings,
+    /// Port timeout in milliseconds.
+    pub timeout: c_int,
+    /// Port private data owned by the iolib.
+    pub pl: *mut GPPortPrivateLibrary,
+    /// Port private data owned by libgphoto2_port.
+    pub pc: *mut GPPortPrivateCore,
+}
+
+/// Serial pins.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+#[repr(C)]
+pub enum GPPin {
+    /// RTS line
+    GP_PIN_RTS = 0,
+    /// DTR line
+    GP_PIN_DTR,
+    /// CTS line
+    GP_PIN_CTS,
+    /// DSR line
+    GP_PIN_DSR,
+    /// CD line
+    GP_PIN_CD,
+    /// RING line
+    GP_PIN_RING,
+}
+
+/// Level to pull specific lines.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+#[repr(C)]
+pub enum GPLevel {
+    /// Pull to low (0V)
+    GP_LEVEL_LOW = 0,
+    /// Pull to high (nV)
+    GP_LEVEL_HIGH = 1,
+}
+
+gp_extern! {
+    pub fn gp_port_new(port: *mut *mut GPPort) -> c_int;
+    pub fn gp_port_free(port: *mut GPPort) -> c_int;
+
+    pub fn gp_port_set_info(port: *mut GPPort, info: GPPortInfo) -> c_int;
+    pub fn gp_port_get_info(port: *mut GPPort, info: *mut GPPortInfo) -> c_int;
+
+    pub fn gp_port_open(port: *mut GPPort) -> c_int;
+    pub fn gp_port_close(port: *mut GPPort) -> c_int;
+
+    pub fn gp_port_reset(port: *mut GPPort) -> c_int;
+
+    // Reading and writing //
+
+    pub fn gp_port_write(port: *mut GPPort, data: *const c_char, size: c_int) -> c_int;
+    pub fn gp_port_read(port: *mut GPPort, data: *mut c_char, size: c_int) -> c_int;
+    pub fn gp_port_check_int(port: *mut GPPort, data: *mut c_char, size: c_int) -> c_int;
+    pub fn gp_port_check_int_fast(port: *mut GPPort, data: *mut c_char, size: c_int) -> c_int;
+
+    // Settings //
+
+    pub fn gp_port_get_timeout(port: *mut GPPort, timeout: *mut c_int) -> c_int;
+    pub fn gp_port_set_timeout(port: *mut GPPort, timeout: c_int) -> c_int;
+
+    pub fn gp_port_set_settings(port: *mut GPPort, settings: GPPortSettings) -> c_int;
+    pub fn gp_port_get_settings(port: *mut GPPort, settings: *mut GPPortSettings) -> c_int;
+
+    // Serial lines //
+
+    pub fn gp_port_get_pin(port: *mut GPPort, pin: GPPin, level: *mut GPLevel) -> c_int;
+    pub fn gp_port_set_pin(port: *mut GPPort, pin: GPPin, level: GPLevel) -> c_int;
+
+    pub fn gp_port_send_break(port: *mut GPPort, duration: c_int) -> c_int;
+    pub fn gp_port_flush(port: *mut GPPort, direction: c_int) -> c_int;
+
+    // USB //
+
+    pub fn gp_port_usb_find_device(port: *mut GPPort, idvendor: c_int, idproduct: c_int) -> c_int;
+    pub fn gp_port_usb_find_device_by_class(
+        port: *mut GPPort,
+        mainclass: c_int,
+        subclass: c_int,
+        protocol: c_int,
+    ) -> c_int;
+    pub fn gp_port_usb_clear_halt(port: *mut GPPort, ep: c_int) -> c_int;
+    pub fn gp_port_usb_msg_write(
+        port: *mut GPPort,
+        request: c_int,
+        value: c_int,
+        index: c_int,
+        bytes: *mut c_char,
+        size: c_int,
+    ) -> c_int;
+    pub fn gp_port_usb_msg_read(
+        port: *mut GPPort,
+        request: c_int,
+        value: c_int,
+        index: c_int,
+        bytes: *mut c_char,
+        size: c_int,
+    ) -> c_int;
+    pub fn gp_port_usb_msg_interface_write(
+        port: *mut GPPort,
+        request: c_int,
+        value: c_int,
+        index: c_int,
+        bytes: *mut c_char,
+        size: c_int,
+    ) -> c_int;
+    pub fn gp_port_usb_msg_interface_read(
+        port: *mut GPPort,
+        request: c_int,
+        value: c_int,
+        index: c_int,
+        bytes: *mut c_char,
+        size: c_int,
+    ) -> c_int;
+    pub fn gp_port_usb_msg_class_write(
+        port: *mut GPPort,
+        request: c_int,
+        value: c_int,
+        index: c_int,
+        bytes: *mut c_char,
+        size: c_int,
+    ) -> c_int;
+    pub fn gp_port_usb_msg_class_read(
+        port: *mut GPPort,
+        request: c_int,
+        value: c_int,
+        index: c_int,
+        bytes: *mut c_char,
+        size: c_int,
+    ) -> c_int;
+
+    // Mass storage //
+
+    pub fn gp_port_seek(port: *mut GPPort, offset: c_int, whence: c_int) -> c_int;
+    pub fn gp_port_send_scsi_cmd(
+        port: *mut GPPort,
+        to_dev: c_int,
+        cmd: *mut c_char,
+        cmd_size: c_int,
+        sense: *mut c_char,
+        sense_size: c_int,
+        data: *mut c_char,
+        data_size: c_int,
+    ) -> c_int;
+
+    // Errors //
+
+    pub fn gp_port_set_error(port: *mut GPPort, format: *const c_char, ...) -> c_int;
+    pub fn gp_port_get_error(port: *mut GPPort) -> *const c_char;
 }
@@ -1,31 +1,33 @@
 // libgphoto2_port/gphoto2/gphoto2-port-info-list.h
 
-use libc::c_void;
+use libc::{c_char, c_int, c_void};
 
-/// The gphoto port type.
-///
-/// Enumeration specifying the port type.
-/// The enum is providing bitmasks, but most code uses it as
-/// just the one specific values.
-#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
-#[repr(C)]
-pub enum GPPortType {
-    /// No specific type associated.
-    GP_PORT_NONE = 0,
-    /// Serial port.
-    GP_PORT_SERIAL = 1 << 0,
-    /// USB port.
-    GP_PORT_USB = 1 << 2,
-    /// Disk / local mountpoint port.
-    GP_PORT_DISK = 1 << 3,
-    /// PTP/IP port.
-    GP_PORT_PTPIP = 1 << 4,
-    /// Direct IO to an usb mass storage device.
-    GP_PORT_USB_DISK_DIRECT = 1 << 5,
-    /// USB Mass Storage raw SCSI port.
-    GP_PORT_USB_SCSI = 1 << 6,
-    /// Generic IP address port.
-    GP_PORT_IP = 1 << 7,
+bitflags! {
+    /// The gphoto port type.
+    ///
+    /// Enumeration specifying the port type.
+    /// The enum is providing bitmasks, but most code uses it as
+    /// just the one specific values.
+    #[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+    #[repr(transparent)]
+    pub struct GPPortType: c_int {
+        /// No specific type associated.
+        const GP_PORT_NONE = 0;
+        /// Serial port.
+        const GP_PORT_SERIAL = 1 << 0;
+        /// USB port.
+        const GP_PORT_USB = 1 << 2;
+        /// Disk / local mountpoint port.
+        const GP_PORT_DISK = 1 << 3;
+        /// PTP/IP port.
+        const GP_PORT_PTPIP = 1 << 4;
+        /// Direct IO to an usb mass storage device.
+        const GP_PORT_USB_DISK_DIRECT = 1 << 5;
+        /// USB Mass Storage raw SCSI port.
+        const GP_PORT_USB_SCSI = 1 << 6;
+        /// Generic IP address port.
+        const GP_PORT_IP = 1 << 7;
+    }
 }
 
 /// Information about the current port.
@@ -53,3 +55,41 @@ pub type GPPortInfo = *mut _GPPortInfo;
 pub struct GPPortInfoList {
     __private: c_void,
 }
+
+gp_extern! {
+    // Port info //
+
+    pub fn gp_port_info_new(info: *mut GPPortInfo) -> c_int;
+    pub fn gp_port_info_get_name(info: GPPortInfo, name: *mut *mut c_char) -> c_int;
+    pub fn gp_port_info_set_name(info: GPPortInfo, name: *const c_char) -> c_int;
+    pub fn gp_port_info_get_path(info: GPPortInfo, path: *mut *mut c_char) -> c_int;
+    pub fn gp_port_info_set_path(info: GPPortInfo, path: *const c_char) -> c_int;
+    pub fn gp_port_info_get_type(info: GPPortInfo, r#type: *mut GPPortType) -> c_int;
+    pub fn gp_port_info_set_type(info: GPPortInfo, r#type: GPPortType) -> c_int;
+    pub fn gp_port_info_get_library_filename(info: GPPortInfo, lib: *mut *mut c_char) -> c_int;
+    pub fn gp_port_info_set_library_filename(info: GPPortInfo, lib: *mut c_char) -> c_int;
+
+    // Port info list //
+
+    pub fn gp_port_info_list_new(list: *mut *mut GPPortInfoList) -> c_int;
+    pub fn gp_port_info_list_free(list: *mut GPPortInfoList) -> c_int;
+
+    pub fn gp_port_info_list_append(list: *mut GPPortInfoList, info: GPPortInfo) -> c_int;
+
+    /// Searches the system for io-drivers and appends them to the list.
+    pub fn gp_port_info_list_load(list: *mut GPPortInfoList) -> c_int;
+
+    pub fn gp_port_info_list_count(list: *mut GPPortInfoList) -> c_int;
+
+    /// Returns the index of the port with the given path, or a negative
+    /// gphoto2 error code.
+    pub fn gp_port_info_list_lookup_path(list: *mut GPPortInfoList, path: *const c_char) -> c_int;
+    /// Returns the index of the port with the given name, or a negative
+    /// gphoto2 error code.
+    pub fn gp_port_info_list_lookup_name(list: *mut GPPortInfoList, name: *const c_char) -> c_int;
+
+    pub fn gp_port_info_list_get_info(list: *mut GPPortInfoList, n: c_int, info: *mut GPPortInfo)
+        -> c_int;
+
+    pub fn gp_port_message_codeset(codeset: *const c_char) -> *const c_char;
+}
@@ -0,0 +1,93 @@
+// libgphoto2_port/gphoto2/gphoto2-port-library.h
+
+use libc::{c_char, c_int};
+
+use crate::{
+    port::{GPLevel, GPPin, GPPort},
+    port_info_list::{GPPortInfoList, GPPortType},
+};
+
+/// The functions of an io library (iolib).
+///
+/// Returned by gp_port_library_operations(), operations the library does not
+/// support are left as `None`. The struct is freed by libgphoto2_port with
+/// `free()`, so it has to be allocated with `malloc()`.
+#[repr(C)]
+pub struct GPPortOperations {
+    pub init: Option<extern "C" fn(port: *mut GPPort) -> c_int>,
+    pub exit: Option<extern "C" fn(port: *mut GPPort) -> c_int>,
+    pub open: Option<extern "C" fn(port: *mut GPPort) -> c_int>,
+    pub close: Option<extern "C" fn(port: *mut GPPort) -> c_int>,
+    pub read: Option<extern "C" fn(port: *mut GPPort, bytes: *mut c_char, size: c_int) -> c_int>,
+    pub check_int: Option<
+        extern "C" fn(port: *mut GPPort, bytes: *mut c_char, size: c_int, timeout: c_int) -> c_int,
+    >,
+    pub write: Option<extern "C" fn(port: *mut GPPort, bytes: *const c_char, size: c_int) -> c_int>,
+    /// Apply `settings_pending` to the port and copy them into `settings`.
+    pub update: Option<extern "C" fn(port: *mut GPPort) -> c_int>,
+
+    // For serial devices
+    pub get_pin: Option<extern "C" fn(port: *mut GPPort, pin: GPPin, level: *mut GPLevel) -> c_int>,
+    pub set_pin: Option<extern "C" fn(port: *mut GPPort, pin: GPPin, level: GPLevel) -> c_int>,
+    pub send_break: Option<extern "C" fn(port: *mut GPPort, duration: c_int) -> c_int>,
+    pub flush: Option<extern "C" fn(port: *mut GPPort, direction: c_int) -> c_int>,
+
+    // For USB devices
+    pub find_device:
+        Option<extern "C" fn(port: *mut GPPort, idvendor: c_int, idproduct: c_int) -> c_int>,
+    pub find_device_by_class: Option<
+        extern "C" fn(port: *mut GPPort, class: c_int, subclass: c_int, protocol: c_int) -> c_int,
+    >,
+    pub clear_halt: Option<extern "C" fn(port: *mut GPPort, ep: c_int) -> c_int>,
+    pub msg_write: Option<GPPortUsbMsgFunc>,
+    pub msg_read: Option<GPPortUsbMsgFunc>,
+    pub msg_interface_write: Option<GPPortUsbMsgFunc>,
+    pub msg_interface_read: Option<GPPortUsbMsgFunc>,
+    pub msg_class_write: Option<GPPortUsbMsgFunc>,
+    pub msg_class_read: Option<GPPortUsbMsgFunc>,
+
+    // For USB disk direct IO devices
+    pub seek: Option<extern "C" fn(port: *mut GPPort, offset: c_int, whence: c_int) -> c_int>,
+
+    // For USB Mass Storage raw SCSI ports
+    pub send_scsi_cmd: Option<
+        extern "C" fn(
+            port: *mut GPPort,
+            to_dev: c_int,
+            cmd: *mut c_char,
+            cmd_size: c_int,
+            sense: *mut c_char,
+            sense_size: c_int,
+            data: *mut c_char,
+            data_size: c_int,
+        ) -> c_int,
+    >,
+
+    pub reset: Option<extern "C" fn(port: *mut GPPort) -> c_int>,
+}
+
+/// A USB control message operation of #GPPortOperations.
+pub type GPPortUsbMsgFunc = extern "C" fn(
+    port: *mut GPPort,
+    request: c_int,
+    value: c_int,
+    index: c_int,
+    bytes: *mut c_char,
+    size: c_int,
+) -> c_int;
+
+/// Returns the port types the io library handles.
+pub type GPPortLibraryType = extern "C" fn() -> GPPortType;
+
+/// Appends the ports the io library provides to `list`.
+pub type GPPortLibraryList = extern "C" fn(list: *mut GPPortInfoList) -> c_int;
+
+/// Returns the operations of the io library, allocated with `malloc()`.
+pub type GPPortLibraryOperations = extern "C" fn() -> *mut GPPortOperations;
+
+// Implemented by the io libraries, not libgphoto2_port
+extern "C" {
+    pub fn gp_port_library_type() -> GPPortType;
+    pub fn gp_port_library_list(list: *mut GPPortInfoList) -> c_int;
+    pub fn gp_port_library_operations() -> *mut GPPortOperations;
+}
@@ -0,0 +1,53 @@
+// libgphoto2_port/gphoto2/gphoto2-port-log.h
+
+use libc::{c_char, c_int, c_uint, c_void};
+
+/// Logging level.
+///
+/// Specifies the logging severity level.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+#[repr(C)]
+pub enum GPLogLevel {
+    /// Log message is an error information.
+    GP_LOG_ERROR = 0,
+    /// Log message is an verbose debug information.
+    GP_LOG_VERBOSE = 1,
+    /// Log message is an debug information.
+    GP_LOG_DEBUG = 2,
+    /// Log message is a data hex dump.
+    GP_LOG_DATA = 3,
+}
+
+/// Used by frontends if they want to be sure their callback function
+/// receives all messages. Defined as the highest debug level.
+pub const GP_LOG_ALL: GPLogLevel = GPLogLevel::GP_LOG_DATA;
+
+/// Logging function hook.
+///
+/// This is the function frontends can use to receive logging information
+/// from the libgphoto2 framework. It is set using gp_log_add_func() and
+/// removed using gp_log_remove_func() and it will then receive the logging
+/// messages of the level specified.
+pub type GPLogFunc =
+    extern "C" fn(level: GPLogLevel, domain: *const c_char, str: *const c_char, data: *mut c_void);
+
+gp_extern! {
+    /// Add a function to get logging information.
+    ///
+    /// Returns the id of the function, to be passed to gp_log_remove_func(),
+    /// or a gphoto2 error code.
+    pub fn gp_log_add_func(level: GPLogLevel, func: GPLogFunc, data: *mut c_void) -> c_int;
+    /// Remove a logging receiving function previously added with gp_log_add_func().
+    pub fn gp_log_remove_func(id: c_int) -> c_int;
+
+    /// Log a debug or error message.
+    pub fn gp_log(level: GPLogLevel, domain: *const c_char, format: *const c_char, ...);
+    /// Log data as a hex dump, prefixed with the formatted message.
+    pub fn gp_log_data(
+        domain: *const c_char,
+        data: *const c_char,
+        size: c_uint,
+        format: *const c_char,
+        ...
+    );
+}
@@ -142,8 +142,9 @@ pub const GP_ERROR_OS_FAILURE: c_int = -114;
 /// Not enough space
 ///
 /// There was not enough free space when uploading a file.
+#[cfg(v2_5_27)]
 pub const GP_ERROR_NO_SPACE: c_int = -115;
 
-extern "C" {
+gp_extern! {
     pub fn gp_result_as_string(result: c_int) -> *const c_char;
 }
@@ -1,6 +1,6 @@
 use libc::{c_char, c_int};
 
-extern "C" {
+gp_extern! {
     pub fn gp_setting_set(id: *const c_char, key: *const c_char, value: *const c_char) -> c_int;
     pub fn gp_setting_get(id: *const c_char, key: *const c_char, value: *const c_char) -> c_int;
 }
@@ -0,0 +1,11 @@
+//! Locations of the drivers built by the `vendored` feature.
+//!
+//! libgphoto2 already looks in these directories by default, they are
+//! exposed so frontends can point the `CAMLIBS` / `IOLIBS` environment
+//! variables at them, e.g. after moving the build output.
+
+/// Directory containing the camera drivers (camlibs).
+pub const CAMLIBS: &str = env!("LIBGPHOTO2_VENDORED_CAMLIBS");
+
+/// Directory containing the port drivers (iolibs).
+pub const IOLIBS: &str = env!("LIBGPHOTO2_VENDORED_IOLIBS");
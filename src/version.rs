@@ -7,6 +7,7 @@ pub enum GPVersionVerbosity {
     GP_VERSION_VERBOSE = 1,
 }
 
-extern "C" {
+gp_extern! {
     pub fn gp_library_version(verbose: GPVersionVerbosity) -> *const *const c_char;
+    pub fn gp_port_library_version(verbose: GPVersionVerbosity) -> *const *const c_char;
 }
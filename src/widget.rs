@@ -38,7 +38,7 @@ pub enum CameraWidgetType {
 pub type CameraWidgetCallback =
     extern "C" fn(camera: *mut Camera, widget: *mut CameraWidget, context: *mut GPContext) -> c_int;
 
-extern "C" {
+gp_extern! {
     pub fn gp_widget_new(
         widget_type: CameraWidgetType,
         label: *const c_char,
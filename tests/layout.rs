@@ -0,0 +1,526 @@
+//! Checks the hand written bindings against the layout the installed
+//! libgphoto2 headers define (generated by `build/layout.rs`).
+
+use std::{
+    mem::{align_of, offset_of, size_of},
+    ptr::addr_of,
+};
+
+use libgphoto2_sys::{
+    abilities_list::*, camera::*, context::*, file::*, filesys::*, port::*, port_info_list::*,
+    port_library::*, port_log::*, result::*, version::*, widget::*,
+};
+
+mod c {
+    include!(concat!(env!("OUT_DIR"), "/layout.rs"));
+}
+
+fn available() {
+    if let Some(err) = c::ERROR {
+        panic!(
+            "the layout of the libgphoto2 headers is unavailable: {}",
+            err
+        );
+    }
+}
+
+fn c_type(name: &str) -> (usize, usize) {
+    available();
+    c::TYPES
+        .iter()
+        .find(|(ty, _, _)| *ty == name)
+        .map(|&(_, size, align)| (size, align))
+        .unwrap_or_else(|| panic!("`{}` is not generated from the headers", name))
+}
+
+fn c_field(ty: &str, field: &str) -> (usize, usize) {
+    available();
+    c::FIELDS
+        .iter()
+        .find(|(t, f, _, _)| *t == ty && *f == field)
+        .map(|&(_, _, offset, size)| (offset, size))
+        .unwrap_or_else(|| panic!("`{}.{}` is not generated from the headers", ty, field))
+}
+
+fn c_constant(name: &str) -> i64 {
+    available();
+    c::CONSTANTS
+        .iter()
+        .find(|(c, _)| *c == name)
+        .map(|&(_, value)| value)
+        .unwrap_or_else(|| panic!("`{}` is not generated from the headers", name))
+}
+
+fn field_size<T, F>(_: impl Fn(&T) -> *const F) -> usize {
+    size_of::<F>()
+}
+
+/// Checks size and alignment of a struct and offset and size of the given
+/// fields, `rust_name => "c_name"` maps renamed fields.
+///
+/// Fields, variants and constants may carry `#[cfg(...)]` attributes.
+macro_rules! check_struct {
+    ($ty:ident { $($(#[$attr:meta])* $field:ident $(=> $c_field:literal)?),* $(,)? }) => {{
+        assert_eq!(
+            (size_of::<$ty>(), align_of::<$ty>()),
+            c_type(stringify!($ty)),
+            "size and alignment of {}",
+            stringify!($ty)
+        );
+        $(
+            $(#[$attr])*
+            assert_eq!(
+                (offset_of!($ty, $field), field_size(|s: &$ty| addr_of!(s.$field))),
+                c_field(stringify!($ty), check_struct!(@name $field $($c_field)?)),
+                "offset and size of {}.{}",
+                stringify!($ty),
+                stringify!($field)
+            );
+        )*
+    }};
+    (@name $field:ident) => {
+        stringify!($field)
+    };
+    (@name $field:ident $c_field:literal) => {
+        $c_field
+    };
+}
+
+/// Checks size and alignment of an enum and the value of each variant.
+macro_rules! check_enum {
+    ($ty:ident { $($(#[$attr:meta])* $variant:ident),* $(,)? }) => {{
+        assert_eq!(
+            (size_of::<$ty>(), align_of::<$ty>()),
+            c_type(stringify!($ty)),
+            "size and alignment of {}",
+            stringify!($ty)
+        );
+        $(
+            $(#[$attr])*
+            assert_eq!(
+                $ty::$variant as i64,
+                c_constant(stringify!($variant)),
+                "value of {}::{}",
+                stringify!($ty),
+                stringify!($variant)
+            );
+        )*
+    }};
+}
+
+/// Like `check_enum!`, for the bitflag types.
+macro_rules! check_flags {
+    ($ty:ident { $($flag:ident),* $(,)? }) => {{
+        assert_eq!(
+            (size_of::<$ty>(), align_of::<$ty>()),
+            c_type(stringify!($ty)),
+            "size and alignment of {}",
+            stringify!($ty)
+        );
+        $(
+            assert_eq!(
+                $ty::$flag.bits() as i64,
+                c_constant(stringify!($flag)),
+                "value of {}::{}",
+                stringify!($ty),
+                stringify!($flag)
+            );
+        )*
+    }};
+}
+
+macro_rules! check_constants {
+    ($($(#[$attr:meta])* $constant:ident),* $(,)?) => {{
+        $(
+            $(#[$attr])*
+            assert_eq!(
+                $constant as i64,
+                c_constant(stringify!($constant)),
+                "value of {}",
+                stringify!($constant)
+            );
+        )*
+    }};
+}
+
+#[test]
+fn test_struct_layouts() {
+    // The reserved fields of CameraAbilities are private, the size check covers them
+    check_struct!(CameraAbilities {
+        model,
+        status,
+        port,
+        speed,
+        operations,
+        file_operations,
+        folder_operations,
+        usb_vendor,
+        usb_product,
+        usb_class,
+        usb_subclass,
+        usb_protocol,
+        library,
+        id,
+        device_type,
+    });
+    check_struct!(CameraFileInfoFile {
+        fields,
+        status,
+        size,
+        mime_type => "type",
+        width,
+        height,
+        permissions,
+        mtime,
+    });
+    check_struct!(CameraFileInfoPreview {
+        fields,
+        status,
+        size,
+        mime_type => "type",
+        width,
+        height,
+    });
+    check_struct!(CameraFileInfoAudio {
+        fields,
+        status,
+        size,
+        mime_type => "type",
+    });
+    check_struct!(CameraFileInfo {
+        preview,
+        file,
+        audio
+    });
+    check_struct!(CameraStorageInformation {
+        fields,
+        basedir,
+        label,
+        description,
+        storage_type => "type",
+        fstype,
+        access,
+        capacitykbytes,
+        freekbytes,
+        freeimages,
+    });
+    check_struct!(CameraFilesystemFuncs {
+        file_list_func,
+        folder_list_func,
+        put_file_func,
+        delete_all_func,
+        get_info_func,
+        set_info_func,
+        make_dir_func,
+        remove_dir_func,
+        get_file_func,
+        #[cfg(v2_5_10)]
+        read_file_func,
+        del_file_func,
+        storage_info_func,
+        unused,
+    });
+    check_struct!(CameraFilePath { name, folder });
+    check_struct!(CameraText { text });
+    check_struct!(Camera {
+        port,
+        fs,
+        functions,
+        pl,
+        pc,
+    });
+    // The reserved pointers are one array, the size check covers the rest
+    assert_eq!(
+        offset_of!(CameraFunctions, reserved),
+        c_field("CameraFunctions", "reserved1").0
+    );
+    check_struct!(CameraFunctions {
+        pre_func,
+        post_func,
+        exit,
+        get_config,
+        set_config,
+        list_config,
+        get_single_config,
+        set_single_config,
+        capture,
+        trigger_capture,
+        capture_preview,
+        summary,
+        manual,
+        about,
+        wait_for_event,
+    });
+    check_struct!(CameraFileHandler { size, read, write });
+    check_struct!(GPPortSettingsSerial {
+        port,
+        speed,
+        bits,
+        parity,
+        stopbits,
+    });
+    check_struct!(GPPortSettingsUSB {
+        inep,
+        outep,
+        intep,
+        config,
+        interface,
+        altsetting,
+        maxpacketsize,
+        port,
+    });
+    check_struct!(GPPortSettingsUsbDiskDirect { path });
+    check_struct!(GPPortSettingsUsbScsi { path });
+    check_struct!(GPPortSettings {
+        serial,
+        usb,
+        usbdiskdirect,
+        usbscsi,
+    });
+    check_struct!(GPPortOperations {
+        init,
+        exit,
+        open,
+        close,
+        read,
+        check_int,
+        write,
+        update,
+        get_pin,
+        set_pin,
+        send_break,
+        flush,
+        find_device,
+        find_device_by_class,
+        clear_halt,
+        msg_write,
+        msg_read,
+        msg_interface_write,
+        msg_interface_read,
+        msg_class_write,
+        msg_class_read,
+        seek,
+        send_scsi_cmd,
+        reset,
+    });
+    check_struct!(GPPort {
+        r#type => "type",
+        settings,
+        settings_pending,
+        timeout,
+        pl,
+        pc,
+    });
+}
+
+#[test]
+fn test_enum_discriminants() {
+    check_enum!(GPContextFeedback {
+        GP_CONTEXT_FEEDBACK_OK,
+        GP_CONTEXT_FEEDBACK_CANCEL,
+    });
+    check_enum!(CameraDriverStatus {
+        GP_DRIVER_STATUS_PRODUCTION,
+        GP_DRIVER_STATUS_TESTING,
+        GP_DRIVER_STATUS_EXPERIMENTAL,
+        GP_DRIVER_STATUS_DEPRECATED,
+    });
+    check_enum!(GphotoDeviceType {
+        GP_DEVICE_STILL_CAMERA,
+        GP_DEVICE_AUDIO_PLAYER,
+    });
+    check_flags!(CameraOperation {
+        GP_OPERATION_NONE,
+        GP_OPERATION_CAPTURE_IMAGE,
+        GP_OPERATION_CAPTURE_VIDEO,
+        GP_OPERATION_CAPTURE_AUDIO,
+        GP_OPERATION_CAPTURE_PREVIEW,
+        GP_OPERATION_CONFIG,
+        GP_OPERATION_TRIGGER_CAPTURE,
+    });
+    check_flags!(CameraFileOperation {
+        GP_FILE_OPERATION_NONE,
+        GP_FILE_OPERATION_DELETE,
+        GP_FILE_OPERATION_PREVIEW,
+        GP_FILE_OPERATION_RAW,
+        GP_FILE_OPERATION_AUDIO,
+        GP_FILE_OPERATION_EXIF,
+    });
+    check_flags!(CameraFolderOperation {
+        GP_FOLDER_OPERATION_NONE,
+        GP_FOLDER_OPERATION_DELETE_ALL,
+        GP_FOLDER_OPERATION_PUT_FILE,
+        GP_FOLDER_OPERATION_MAKE_DIR,
+        GP_FOLDER_OPERATION_REMOVE_DIR,
+    });
+    check_enum!(CameraCaptureType {
+        GP_CAPTURE_IMAGE,
+        GP_CAPTURE_MOVIE,
+        GP_CAPTURE_SOUND,
+    });
+    check_enum!(CameraEventType {
+        GP_EVENT_UNKNOWN,
+        GP_EVENT_TIMEOUT,
+        GP_EVENT_FILE_ADDED,
+        GP_EVENT_FOLDER_ADDED,
+        GP_EVENT_CAPTURE_COMPLETE,
+        #[cfg(v2_5_27)]
+        GP_EVENT_FILE_CHANGED,
+    });
+    check_enum!(CameraFileType {
+        GP_FILE_TYPE_PREVIEW,
+        GP_FILE_TYPE_NORMAL,
+        GP_FILE_TYPE_RAW,
+        GP_FILE_TYPE_AUDIO,
+        GP_FILE_TYPE_EXIF,
+        GP_FILE_TYPE_METADATA,
+    });
+    check_enum!(CameraFileAccessType {
+        GP_FILE_ACCESSTYPE_MEMORY,
+        GP_FILE_ACCESSTYPE_FD,
+        GP_FILE_ACCESSTYPE_HANDLER,
+    });
+    check_flags!(CameraFileInfoFields {
+        GP_FILE_INFO_NONE,
+        GP_FILE_INFO_TYPE,
+        GP_FILE_INFO_SIZE,
+        GP_FILE_INFO_WIDTH,
+        GP_FILE_INFO_HEIGHT,
+        GP_FILE_INFO_PERMISSIONS,
+        GP_FILE_INFO_STATUS,
+        GP_FILE_INFO_MTIME,
+        GP_FILE_INFO_ALL,
+    });
+    check_flags!(CameraFilePermissions {
+        GP_FILE_PERM_NONE,
+        GP_FILE_PERM_READ,
+        GP_FILE_PERM_DELETE,
+        GP_FILE_PERM_ALL,
+    });
+    check_enum!(CameraFileStatus {
+        GP_FILE_STATUS_NOT_DOWNLOADED,
+        GP_FILE_STATUS_DOWNLOADED,
+    });
+    check_flags!(CameraStorageInfoFields {
+        GP_STORAGEINFO_BASE,
+        GP_STORAGEINFO_LABEL,
+        GP_STORAGEINFO_DESCRIPTION,
+        GP_STORAGEINFO_ACCESS,
+        GP_STORAGEINFO_STORAGETYPE,
+        GP_STORAGEINFO_FILESYSTEMTYPE,
+        GP_STORAGEINFO_MAXCAPACITY,
+        GP_STORAGEINFO_FREESPACEKBYTES,
+        GP_STORAGEINFO_FREESPACEIMAGES,
+    });
+    check_enum!(CameraStorageType {
+        GP_STORAGEINFO_ST_UNKNOWN,
+        GP_STORAGEINFO_ST_FIXED_ROM,
+        GP_STORAGEINFO_ST_REMOVABLE_ROM,
+        GP_STORAGEINFO_ST_FIXED_RAM,
+        GP_STORAGEINFO_ST_REMOVABLE_RAM,
+    });
+    check_enum!(CameraStorageAccessType {
+        GP_STORAGEINFO_AC_READWRITE,
+        GP_STORAGEINFO_AC_READONLY,
+        GP_STORAGEINFO_AC_READONLY_WITH_DELETE,
+    });
+    check_enum!(CameraStorageFilesystemType {
+        GP_STORAGEINFO_FST_UNDEFINED,
+        GP_STORAGEINFO_FST_GENERICFLAT,
+        GP_STORAGEINFO_FST_GENERICHIERARCHICAL,
+        GP_STORAGEINFO_FST_DCF,
+    });
+    check_enum!(CameraWidgetType {
+        GP_WIDGET_WINDOW,
+        GP_WIDGET_SECTION,
+        GP_WIDGET_TEXT,
+        GP_WIDGET_RANGE,
+        GP_WIDGET_TOGGLE,
+        GP_WIDGET_RADIO,
+        GP_WIDGET_MENU,
+        GP_WIDGET_BUTTON,
+        GP_WIDGET_DATE,
+    });
+    check_flags!(GPPortType {
+        GP_PORT_NONE,
+        GP_PORT_SERIAL,
+        GP_PORT_USB,
+        GP_PORT_DISK,
+        GP_PORT_PTPIP,
+        GP_PORT_USB_DISK_DIRECT,
+        GP_PORT_USB_SCSI,
+        GP_PORT_IP,
+    });
+    check_enum!(GPPortSerialParity {
+        GP_PORT_SERIAL_PARITY_OFF,
+        GP_PORT_SERIAL_PARITY_EVEN,
+        GP_PORT_SERIAL_PARITY_ODD,
+    });
+    check_enum!(GPPin {
+        GP_PIN_RTS,
+        GP_PIN_DTR,
+        GP_PIN_CTS,
+        GP_PIN_DSR,
+        GP_PIN_CD,
+        GP_PIN_RING,
+    });
+    check_enum!(GPLevel {
+        GP_LEVEL_LOW,
+        GP_LEVEL_HIGH,
+    });
+    check_enum!(GPLogLevel {
+        GP_LOG_ERROR,
+        GP_LOG_VERBOSE,
+        GP_LOG_DEBUG,
+        GP_LOG_DATA,
+    });
+    check_enum!(GPVersionVerbosity {
+        GP_VERSION_SHORT,
+        GP_VERSION_VERBOSE,
+    });
+}
+
+#[test]
+fn test_constants() {
+    check_constants!(
+        GP_PORT_MAX_BUF_LEN,
+        GP_PORT_USB_ENDPOINT_IN,
+        GP_PORT_USB_ENDPOINT_OUT,
+        GP_PORT_USB_ENDPOINT_INT,
+        GP_OK,
+        GP_ERROR,
+        GP_ERROR_BAD_PARAMETERS,
+        GP_ERROR_NO_MEMORY,
+        GP_ERROR_LIBRARY,
+        GP_ERROR_UNKNOWN_PORT,
+        GP_ERROR_NOT_SUPPORTED,
+        GP_ERROR_IO,
+        GP_ERROR_FIXED_LIMIT_EXCEEDED,
+        GP_ERROR_TIMEOUT,
+        GP_ERROR_IO_SUPPORTED_SERIAL,
+        GP_ERROR_IO_SUPPORTED_USB,
+        GP_ERROR_IO_INIT,
+        GP_ERROR_IO_READ,
+        GP_ERROR_IO_WRITE,
+        GP_ERROR_IO_UPDATE,
+        GP_ERROR_IO_SERIAL_SPEED,
+        GP_ERROR_IO_USB_CLEAR_HALT,
+        GP_ERROR_IO_USB_FIND,
+        GP_ERROR_IO_USB_CLAIM,
+        GP_ERROR_IO_LOCK,
+        GP_ERROR_HAL,
+        GP_ERROR_CORRUPTED_DATA,
+        GP_ERROR_FILE_EXISTS,
+        GP_ERROR_MODEL_NOT_FOUND,
+        GP_ERROR_DIRECTORY_NOT_FOUND,
+        GP_ERROR_FILE_NOT_FOUND,
+        GP_ERROR_DIRECTORY_EXISTS,
+        GP_ERROR_CAMERA_BUSY,
+        GP_ERROR_PATH_NOT_ABSOLUTE,
+        GP_ERROR_CANCEL,
+        GP_ERROR_CAMERA_ERROR,
+        GP_ERROR_OS_FAILURE,
+        #[cfg(v2_5_27)]
+        GP_ERROR_NO_SPACE,
+    );
+}